@@ -1,28 +1,229 @@
 //! ```cargo
 //! [dependencies]
 //! rand = "0.8.3"
+//! num-complex = { version = "0.4", optional = true }
+//!
+//! [features]
+//! complex = ["num-complex"]
 //! ```
 
+/*
+	The matrix API is a library carried in a script: main only demonstrates
+	a few operations, so most of it is unused outside the test build. That
+	build still reports dead code, so every part of the API needs a test.
+*/
+#![cfg_attr(not(test), allow(dead_code))]
+
+use std::cell::OnceCell;
 use std::fmt;
-use rand::Rng;
+use std::io::BufRead;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+#[cfg(feature = "complex")]
+use num_complex::Complex;
+
+#[derive(Debug, Clone, PartialEq)]
+enum MatrixError
+{
+	InvalidLength { expected: usize, found: usize },
+	NotSquare { m: u32, n: u32 },
+	DimensionMismatch { expected: (u32, u32), found: (u32, u32) },
+	Singular,
+	RankDeficient,
+	ZeroTrace,
+	NegativeDeterminant,
+	DidNotConverge { iterations: usize },
+	RaggedRow { row: usize, expected: usize, found: usize },
+	EmptyInput,
+	ZeroVector,
+	TooLargeForCofactor { m: u32 },
+	Parse { line: usize, token: String },
+	Io(String),
+	NotSymmetric,
+	OutOfBounds { index: u32, bound: u32 },
+	NotPositiveDefinite,
+	InvalidNpy(String),
+	ZeroDiagonal { index: u32 },
+	DimensionOverflow,
+}
+
+/*
+	The shape category of a matrix, as reported by shape_info.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ShapeInfo
+{
+	Empty,
+	Scalar,
+	RowVector,
+	ColumnVector,
+	Square,
+	Tall,
+	Wide,
+}
+
+/*
+	The axis along which a reduction collapses a matrix. Rows collapses
+	the rows into one (a 1 x n result), Cols collapses the columns (m x 1).
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis
+{
+	Rows,
+	Cols,
+}
+
+/*
+	An elementary row operation, as recorded by rref_steps:
+	swapping two rows, scaling a row, or adding a multiple of
+	the source row to the target row.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RowOp
+{
+	Swap(u32, u32),
+	Scale { row: u32, factor: f64 },
+	AddScaled { target: u32, source: u32, factor: f64 },
+}
+
+/*
+	The order in which entries are read and written when relabeling
+	a matrix: row by row (C, numpy's default) or column by column (Fortran).
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Order
+{
+	RowMajor,
+	ColMajor,
+}
+
+/*
+	How many solutions the linear system A*x = b has, as reported by
+	Matrix::solvability.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Solvability
+{
+	UniqueSolution,
+	NoSolution,
+	InfiniteSolutions,
+}
+
+/*
+	Formatting options for Matrix::format_with, built up with
+	chained calls such as DisplayConfig::new().precision(3).
+*/
+#[derive(Debug, Clone, PartialEq)]
+struct DisplayConfig
+{
+	precision: Option<usize>,
+	scientific: bool,
+	separator: String,
+}
+
+/*
+	The default tolerance below which values such as pivots are treated
+	as zero, e.g. when deciding whether a matrix is singular.
+*/
+const DEFAULT_TOL: f64 = 1e-12;
+
+/*
+	The largest size try_det will expand by cofactors by default. The
+	expansion takes factorial time, so beyond this it would effectively hang.
+*/
+const COFACTOR_LIMIT: u32 = 10;
+
+/*
+	The magic string that opens every .npy file.
+*/
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/*
+	Upper bound on the number of sweeps performed by Jacobi rotation methods.
+*/
+const JACOBI_MAX_SWEEPS: usize = 100;
 
 struct Matrix
 {
 	m: u32,
 	n: u32,
 	entries: Vec<f64>,
+	/*
+		The determinant, computed on the first call to det. Every method
+		that mutates the entries must clear it through invalidate.
+	*/
+	det_cache: OnceCell<f64>,
 }
 
 impl Matrix
 {
 	pub fn new(m: u32, n: u32) -> Matrix
+	{
+		Matrix::filled(m, n, 0f64)
+	}
+
+	/*
+		Creates an m x n matrix with every entry set to value.
+	*/
+	pub fn filled(m: u32, n: u32, value: f64) -> Matrix
 	{
 		Matrix
 		{
 			m,
 			n,
-			entries: vec![0f64; (m * n) as usize],
+			entries: vec![value; m as usize * n as usize],
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	pub fn identity(n: u32) -> Matrix
+	{
+		Matrix::from_fn(n, n, |i, j| if i == j { 1f64 } else { 0f64 })
+	}
+
+	/*
+		Creates an m x n matrix whose entry (i, j) is f(i, j).
+	*/
+	pub fn from_fn<F: Fn(u32, u32) -> f64>(m: u32, n: u32, f: F) -> Matrix
+	{
+		let mut res = Matrix::new(m, n);
+
+		for idx in 0..res.entries.len()
+		{
+			let (i, j) = res.get_coords(idx);
+			res.entries[idx] = f(i, j);
+		}
+
+		res
+	}
+
+	/*
+		Reconstructs a matrix from its dimensions and row-major entries,
+		taking ownership of the buffer without copying it.
+	*/
+	pub fn from_parts(m: u32, n: u32, entries: Vec<f64>) -> Result<Matrix, MatrixError>
+	{
+		/*
+			The length is computed in usize, as m * n can overflow u32. A
+			product that overflows even usize can't match any buffer.
+		*/
+		let expected = (m as usize).checked_mul(n as usize);
+
+		if expected != Some(entries.len())
+		{
+			return Err(MatrixError::InvalidLength { expected: expected.unwrap_or(usize::MAX), found: entries.len() });
 		}
+
+		Ok(Matrix { m, n, entries, det_cache: OnceCell::new() })
+	}
+
+	/*
+		Consumes the matrix, returning its dimensions and row-major entries.
+		This is the inverse of from_parts.
+	*/
+	pub fn into_parts(self) -> (u32, u32, Vec<f64>)
+	{
+		(self.m, self.n, self.entries)
 	}
 
 	/*
@@ -51,7 +252,7 @@ impl Matrix
 
 		for j in 0..self.n
 		{
-			let idx = self.get_index(i as u32, j as u32);
+			let idx = self.get_index(i as u32, j);
 			row.push(self.entries[idx]);
 		}
 
@@ -67,13 +268,113 @@ impl Matrix
 
 		for i in 0..self.m
 		{
-			let idx = self.get_index(i as u32, j as u32);
+			let idx = self.get_index(i, j as u32);
 			col.push(self.entries[idx]);
 		}
 
 		col
 	}
 
+	/*
+		Discards the cached determinant after the entries have changed.
+	*/
+	fn invalidate(&mut self)
+	{
+		self.det_cache.take();
+	}
+
+	/*
+		Overwrites the entry at row i and column j.
+	*/
+	pub fn set(&mut self, i: u32, j: u32, value: f64)
+	{
+		if i >= self.m || j >= self.n
+		{
+			panic!("cannot set an entry outside of the matrix");
+		}
+
+		self.invalidate();
+
+		let idx = self.get_index(i, j);
+		self.entries[idx] = value;
+	}
+
+	/*
+		Swaps rows a and b in place.
+	*/
+	fn swap_rows(&mut self, a: u32, b: u32)
+	{
+		self.invalidate();
+
+		for j in 0..self.n
+		{
+			let (l, r) = (self.get_index(a, j), self.get_index(b, j));
+			self.entries.swap(l, r);
+		}
+	}
+
+	/*
+		Applies a plane rotation to columns p and q in place,
+		replacing them with c*p - s*q and s*p + c*q.
+	*/
+	fn rotate_cols(&mut self, p: u32, q: u32, c: f64, s: f64)
+	{
+		self.invalidate();
+
+		for i in 0..self.m
+		{
+			let (ip, iq) = (self.get_index(i, p), self.get_index(i, q));
+			let (a, b)   = (self.entries[ip], self.entries[iq]);
+
+			self.entries[ip] = c * a - s * b;
+			self.entries[iq] = s * a + c * b;
+		}
+	}
+
+	/*
+		Applies a plane rotation to rows p and q in place,
+		replacing them with c*p - s*q and s*p + c*q.
+	*/
+	fn rotate_rows(&mut self, p: u32, q: u32, c: f64, s: f64)
+	{
+		self.invalidate();
+
+		for j in 0..self.n
+		{
+			let (pj, qj) = (self.get_index(p, j), self.get_index(q, j));
+			let (a, b)   = (self.entries[pj], self.entries[qj]);
+
+			self.entries[pj] = c * a - s * b;
+			self.entries[qj] = s * a + c * b;
+		}
+	}
+
+	/*
+		Checks whether the matrix is square and equal to its transpose,
+		up to a rounding tolerance relative to the largest entry magnitude.
+	*/
+	fn is_symmetric(&self) -> bool
+	{
+		let tol = DEFAULT_TOL * self.max_abs();
+
+		self.m == self.n && self.entries
+			.iter()
+			.enumerate()
+			.all(|(idx, entry)| {
+				let (row, col) = self.get_coords(idx);
+				let mirror     = self.entries[self.get_index(col, row)];
+				(entry - mirror).abs() <= tol
+			})
+	}
+
+	/*
+		Copies out the m x n block whose top-left entry is at (row, col).
+	*/
+	fn block(&self, row: u32, col: u32, m: u32, n: u32) -> Matrix
+	{
+		Matrix::from_fn(m, n, |i, j| self.entries[self.get_index(row + i, col + j)])
+	}
+
 	/*
 		Produces a submatrix with row i and column j missing.
 	*/
@@ -92,7 +393,34 @@ impl Matrix
 						 })
 						 .map(|(_, elem)| *elem)
 						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	/*
+		Builds the 3x3 skew-symmetric matrix [v]x of a 3D vector v,
+		such that [v]x * w equals the cross product v x w.
+	*/
+	pub fn skew_symmetric_3d(v: &[f64]) -> Result<Matrix, MatrixError>
+	{
+		if v.len() != 3
+		{
+			return Err(MatrixError::InvalidLength { expected: 3, found: v.len() });
 		}
+
+		let (x, y, z) = (v[0], v[1], v[2]);
+
+		Ok(Matrix
+		{
+			m: 3,
+			n: 3,
+			entries: vec![
+				0f64,  -z,    y,
+				z,     0f64, -x,
+				-y,    x,     0f64,
+			],
+			det_cache: OnceCell::new(),
+		})
 	}
 
 	pub fn add(&self, other: &Matrix) -> Matrix
@@ -111,14 +439,34 @@ impl Matrix
 						 .zip(other.entries.iter())
 						 .map(|(l, r)| l + r)
 						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
 		}
 	}
 
 	pub fn mul(&self, other: &Matrix) -> Matrix
 	{
-		if (self.m, self.n) != (other.n, other.m)
+		self.mul_with(other, dot)
+	}
+
+	/*
+		Like mul, but accumulates each dot product with compensated
+		summation, which stays accurate when terms of very different
+		magnitude cancel out.
+	*/
+	pub fn mul_accurate(&self, other: &Matrix) -> Matrix
+	{
+		self.mul_with(other, compensated_dot)
+	}
+
+	/*
+		Multiplies two matrices, computing every entry of the result as
+		dot_prod(row, column).
+	*/
+	fn mul_with(&self, other: &Matrix, dot_prod: fn(&[f64], &[f64]) -> f64) -> Matrix
+	{
+		if self.n != other.m
 		{
-			panic!("cannot multiply matrices of differing inverted dimensions");
+			panic!("cannot multiply matrices with differing inner dimensions");
 		}
 
 		let mut res = Matrix::new(self.m, other.n);
@@ -126,23 +474,235 @@ impl Matrix
 		for i in 0..self.m
 		{
 			let row_vec = self.get_row_vec(i as usize);
-			for j in 0..self.n
+			for j in 0..other.n
 			{
-				let idx      = res.get_index(i as u32, j as u32);
-				let col_vec  = other.get_col_vec(j as usize);
-				let dot_prod = row_vec.iter()
-									  .zip(col_vec.iter())
-									  .map(|(l, r)| l * r)
-									  .fold(0.0f64, |sum, r| sum + r);
+				let idx     = res.get_index(i, j);
+				let col_vec = other.get_col_vec(j as usize);
 
-				res.entries[idx] = dot_prod;
+				res.entries[idx] = dot_prod(&row_vec, &col_vec);
 			}
 		}
 
 		res
 	}
 
+	/*
+		Multiplies the matrix with a column vector of length n.
+	*/
+	pub fn mul_vec(&self, v: &[f64]) -> Result<Vec<f64>, MatrixError>
+	{
+		if v.len() != self.n as usize
+		{
+			return Err(MatrixError::InvalidLength { expected: self.n as usize, found: v.len() });
+		}
+
+		Ok((0..self.m as usize)
+			.map(|i| dot(&self.get_row_vec(i), v))
+			.collect::<Vec<_>>())
+	}
+
+	/*
+		Computes an LU decomposition with partial pivoting. Both factors are
+		packed into a single matrix: the part below the diagonal holds L (whose
+		unit diagonal is implied) and the rest holds U. Alongside it we return
+		the row permutation and the number of row swaps that were performed.
+	*/
+	fn lu_packed(&self) -> Result<(Matrix, Vec<usize>, usize), MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		let n        = self.n;
+		let mut lu   = self.clone();
+		let mut perm = (0..n as usize).collect::<Vec<_>>();
+		let mut swaps = 0;
+
+		for k in 0..n
+		{
+			/*
+				We pick the row with the largest magnitude in column k as
+				the pivot, which keeps the multipliers bounded by 1.
+			*/
+			let mut pivot = k;
+
+			for i in (k + 1)..n
+			{
+				if lu.entries[lu.get_index(i, k)].abs() > lu.entries[lu.get_index(pivot, k)].abs()
+				{
+					pivot = i;
+				}
+			}
+
+			if pivot != k
+			{
+				lu.swap_rows(k, pivot);
+				perm.swap(k as usize, pivot as usize);
+				swaps += 1;
+			}
+
+			let diag = lu.entries[lu.get_index(k, k)];
+
+			if diag == 0f64
+			{
+				continue;
+			}
+
+			for i in (k + 1)..n
+			{
+				let idx    = lu.get_index(i, k);
+				let factor = lu.entries[idx] / diag;
+
+				lu.entries[idx] = factor;
+
+				for j in (k + 1)..n
+				{
+					let (dst, src) = (lu.get_index(i, j), lu.get_index(k, j));
+					lu.entries[dst] -= factor * lu.entries[src];
+				}
+			}
+		}
+
+		Ok((lu, perm, swaps))
+	}
+
+	/*
+		Returns the largest entry magnitude, or 0 for an empty matrix.
+	*/
+	fn max_abs(&self) -> f64
+	{
+		self.entries.iter().fold(0f64, |acc, entry| acc.max(entry.abs()))
+	}
+
+	/*
+		Returns the magnitude at or below which an LU pivot counts as zero.
+		Rounding in the elimination grows with n and with the size of the
+		entries, so DEFAULT_TOL is taken relative to n * max|a_ij| and the
+		verdict doesn't change when the whole matrix is scaled.
+	*/
+	fn pivot_tol(&self) -> f64
+	{
+		DEFAULT_TOL * self.n as f64 * self.max_abs()
+	}
+
+	/*
+		Solves L*U*x = P*b by forward and back substitution, given the
+		packed factors and permutation produced by lu_packed.
+	*/
+	fn lu_substitute(lu: &Matrix, perm: &[usize], b: &[f64]) -> Vec<f64>
+	{
+		let n     = lu.n;
+		let mut x = perm.iter().map(|&p| b[p]).collect::<Vec<_>>();
+
+		for i in 0..n
+		{
+			for k in 0..i
+			{
+				x[i as usize] -= lu.entries[lu.get_index(i, k)] * x[k as usize];
+			}
+		}
+
+		for i in (0..n).rev()
+		{
+			for k in (i + 1)..n
+			{
+				x[i as usize] -= lu.entries[lu.get_index(i, k)] * x[k as usize];
+			}
+
+			x[i as usize] /= lu.entries[lu.get_index(i, i)];
+		}
+
+		x
+	}
+
+	/*
+		Factors the matrix for solving, failing if any pivot is within
+		pivot_tol as in det_sign.
+	*/
+	fn lu_for_solve(&self) -> Result<(Matrix, Vec<usize>), MatrixError>
+	{
+		let (lu, perm, _) = self.lu_packed()?;
+		let tol           = self.pivot_tol();
+
+		if (0..lu.n).any(|k| lu.entries[lu.get_index(k, k)].abs() <= tol)
+		{
+			return Err(MatrixError::Singular);
+		}
+
+		Ok((lu, perm))
+	}
+
+	/*
+		Returns the sign of the determinant (-1, 0 or 1) without computing its
+		magnitude. The sign is the parity of the row permutation combined with
+		the signs of the pivots of U, where a pivot within pivot_tol counts as
+		zero.
+	*/
+	pub fn det_sign(&self) -> Result<i32, MatrixError>
+	{
+		let (lu, _, swaps) = self.lu_packed()?;
+		let tol      = self.pivot_tol();
+		let mut sign = if swaps % 2 == 0 { 1 } else { -1 };
+
+		for k in 0..self.n
+		{
+			let pivot = lu.entries[lu.get_index(k, k)];
+
+			if pivot.abs() <= tol
+			{
+				return Ok(0);
+			}
+
+			if pivot < 0f64
+			{
+				sign = -sign;
+			}
+		}
+
+		Ok(sign)
+	}
+
+	/*
+		Computes the determinant from an LU factorization: the product of
+		the pivots of U, negated for every row swap. This takes O(n^3)
+		operations rather than the factorial cost of a Laplace expansion.
+	*/
+	fn lu_det(&self) -> Result<f64, MatrixError>
+	{
+		self.det_with_tol(0f64)
+	}
+
+	/*
+		Computes the determinant from an LU factorization, treating any pivot
+		with magnitude at most tol as zero. A numerically singular matrix thus
+		yields exactly 0 rather than a tiny value made up of rounding error.
+	*/
+	pub fn det_with_tol(&self, tol: f64) -> Result<f64, MatrixError>
+	{
+		let (lu, _, swaps) = self.lu_packed()?;
+		let pivots = (0..self.n).map(|k| lu.entries[lu.get_index(k, k)]).collect::<Vec<_>>();
+
+		if pivots.iter().any(|pivot| pivot.abs() <= tol)
+		{
+			return Ok(0f64);
+		}
+
+		let sign = if swaps % 2 == 0 { 1f64 } else { -1f64 };
+
+		Ok(pivots.iter().fold(sign, |det, pivot| det * pivot))
+	}
+
+	/*
+		Computes the determinant, caching it so that repeated calls on
+		an unchanged matrix don't redo the expansion.
+	*/
 	pub fn det(&self) -> f64
+	{
+		*self.det_cache.get_or_init(|| self.compute_det())
+	}
+
+	fn compute_det(&self) -> f64
 	{
 		if self.m != self.n
 		{
@@ -151,7 +711,11 @@ impl Matrix
 
 		match self.m
 		{
-			0 => 0f64,
+			/*
+				The determinant of the empty matrix is the empty product, 1,
+				which keeps the Laplace expansion consistent at every size.
+			*/
+			0 => 1f64,
 			1 => self.entries[0],
 			2 => {
 				/*
@@ -168,6 +732,23 @@ impl Matrix
 				);
 				a * d - b * c
 			}
+			3 => {
+				/*
+					For a 3x3-matrix A with entries
+					[a b c]
+					[d e f]
+					[g h i]
+					we use the rule of Sarrus,
+					det(A) = aei + bfg + cdh - ceg - bdi - afh,
+					which avoids recursing into three 2x2 submatrices.
+				*/
+				let (a, b, c, d, e, f, g, h, i) = (
+					self.entries[0], self.entries[1], self.entries[2],
+					self.entries[3], self.entries[4], self.entries[5],
+					self.entries[6], self.entries[7], self.entries[8]
+				);
+				a * e * i + b * f * g + c * d * h - c * e * g - b * d * i - a * f * h
+			}
 			_ => {
 				/*
 					We use a Laplace expansion to compute the cofactors
@@ -181,7 +762,7 @@ impl Matrix
 					let idx   = self.get_index(0, col);
 					let entry = self.entries[idx];
 					let subm  = self.sub_matrix(0, col);
-					let coef  = (-1f64).powf((0 + col) as f64);	
+					let coef  = (-1f64).powf(col as f64);	
 
 					let val = coef * entry * subm.det();
 
@@ -192,58 +773,4117 @@ impl Matrix
 			},
 		}
 	}
-}
 
-impl fmt::Display for Matrix
-{
+	/*
+		Checks whether every off-diagonal entry of a square matrix
+		lies within tol of zero.
+	*/
+	pub fn is_diagonal(&self, tol: f64) -> bool
+	{
+		if self.m != self.n
+		{
+			return false;
+		}
+
+		self.entries
+			.iter()
+			.enumerate()
+			.all(|(idx, entry)| {
+				let (row, col) = self.get_coords(idx);
+				row == col || entry.abs() <= tol
+			})
+	}
+
+	/*
+		Returns a copy of the matrix with every off-diagonal entry set to zero.
+	*/
+	pub fn diagonal_matrix(&self) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries
+						 .iter()
+						 .enumerate()
+						 .map(|(idx, entry)| {
+							let (row, col) = self.get_coords(idx);
+							if row == col { *entry } else { 0f64 }
+						 })
+						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	/*
+		Computes the Euclidean norm of the residual A*x - b, which tells
+		how accurately x solves the system A*x = b.
+	*/
+	pub fn residual(&self, x: &[f64], b: &[f64]) -> Result<f64, MatrixError>
+	{
+		if b.len() != self.m as usize
+		{
+			return Err(MatrixError::InvalidLength { expected: self.m as usize, found: b.len() });
+		}
+
+		let ax = self.mul_vec(x)?;
+
+		Ok(ax.iter()
+			 .zip(b.iter())
+			 .map(|(l, r)| (l - r) * (l - r))
+			 .fold(0.0f64, |sum, r| sum + r)
+			 .sqrt())
+	}
+
+	pub fn transpose(&self) -> Matrix
+	{
+		let mut res = Matrix::new(self.n, self.m);
+
+		for (idx, entry) in self.entries.iter().enumerate()
+		{
+			let (i, j) = self.get_coords(idx);
+			let dst    = res.get_index(j, i);
+			res.entries[dst] = *entry;
+		}
+
+		res
+	}
+
+	/*
+		Computes the inverse using Gauss-Jordan elimination with partial
+		pivoting: the row operations reducing A to I turn I into A^-1.
+		A pivot within pivot_tol means the matrix is singular, the same test
+		det_sign applies.
+	*/
+	pub fn inverse(&self) -> Result<Matrix, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		let n       = self.n;
+		let tol     = self.pivot_tol();
+		let mut a   = self.clone();
+		let mut inv = Matrix::identity(n);
+
+		for k in 0..n
+		{
+			let mut pivot = k;
+
+			for i in (k + 1)..n
+			{
+				if a.entries[a.get_index(i, k)].abs() > a.entries[a.get_index(pivot, k)].abs()
+				{
+					pivot = i;
+				}
+			}
+
+			if a.entries[a.get_index(pivot, k)].abs() <= tol
+			{
+				return Err(MatrixError::Singular);
+			}
+
+			a.swap_rows(k, pivot);
+			inv.swap_rows(k, pivot);
+
+			let diag = a.entries[a.get_index(k, k)];
+
+			for j in 0..n
+			{
+				let idx = a.get_index(k, j);
+				a.entries[idx]   /= diag;
+				inv.entries[idx] /= diag;
+			}
+
+			for i in (0..n).filter(|&i| i != k)
+			{
+				let factor = a.entries[a.get_index(i, k)];
+
+				if factor == 0f64
+				{
+					continue;
+				}
+
+				for j in 0..n
+				{
+					let (dst, src) = (a.get_index(i, j), a.get_index(k, j));
+					a.entries[dst]   -= factor * a.entries[src];
+					inv.entries[dst] -= factor * inv.entries[src];
+				}
+			}
+		}
+
+		Ok(inv)
+	}
+
+	/*
+		Computes the Moore-Penrose pseudo-inverse via the normal equations:
+		(A^T A)^-1 A^T for tall matrices and A^T (A A^T)^-1 for wide ones.
+		The matrix must have full column (respectively row) rank.
+	*/
+	pub fn pinv(&self) -> Result<Matrix, MatrixError>
+	{
+		/*
+			Forming the Gram matrix squares the scale of A, but inverse judges
+			its pivots relative to the Gram matrix's own entries, so the rank
+			test doesn't depend on the scale of A.
+		*/
+		let at = self.transpose();
+
+		if self.m >= self.n
+		{
+			let gram = at.mul(self).inverse().map_err(|_| MatrixError::RankDeficient)?;
+			Ok(gram.mul(&at))
+		}
+		else
+		{
+			let gram = self.mul(&at).inverse().map_err(|_| MatrixError::RankDeficient)?;
+			Ok(at.mul(&gram))
+		}
+	}
+
+	/*
+		Computes the n x n sample covariance matrix, treating each row as an
+		observation and each column as a variable. We center every column on
+		its mean and compute (X_c^T X_c) / (m - 1). With fewer than two
+		observations the covariance is undefined and an empty matrix is returned.
+	*/
+	pub fn covariance(&self) -> Matrix
+	{
+		if self.m < 2
+		{
+			return Matrix::new(0, 0);
+		}
+
+		let means = (0..self.n as usize)
+			.map(|j| self.get_col_vec(j).iter().sum::<f64>() / self.m as f64)
+			.collect::<Vec<_>>();
+
+		let centered = Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries
+						 .iter()
+						 .enumerate()
+						 .map(|(idx, entry)| {
+							let (_, col) = self.get_coords(idx);
+							entry - means[col as usize]
+						 })
+						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		};
+
+		let mut cov = centered.transpose().mul(&centered);
+
+		for entry in cov.entries.iter_mut()
+		{
+			*entry /= (self.m - 1) as f64;
+		}
+
+		cov
+	}
+
+	/*
+		Returns a copy of the matrix with the entries below
+		the main diagonal set to zero.
+	*/
+	pub fn upper_triangular(&self) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries
+						 .iter()
+						 .enumerate()
+						 .map(|(idx, entry)| {
+							let (row, col) = self.get_coords(idx);
+							if row <= col { *entry } else { 0f64 }
+						 })
+						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	/*
+		Returns a copy of the matrix with the entries above
+		the main diagonal set to zero.
+	*/
+	pub fn lower_triangular(&self) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries
+						 .iter()
+						 .enumerate()
+						 .map(|(idx, entry)| {
+							let (row, col) = self.get_coords(idx);
+							if row >= col { *entry } else { 0f64 }
+						 })
+						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	/*
+		Applies f to every entry, producing a new matrix.
+	*/
+	pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries.iter().map(|entry| f(*entry)).collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	/*
+		Like map, but also passes the row and column of each entry to f.
+	*/
+	pub fn map_indexed<F: Fn(u32, u32, f64) -> f64>(&self, f: F) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries
+						 .iter()
+						 .enumerate()
+						 .map(|(idx, entry)| {
+							let (row, col) = self.get_coords(idx);
+							f(row, col, *entry)
+						 })
+						 .collect::<Vec<_>>(),
+			det_cache: OnceCell::new(),
+		}
+	}
+
+	/*
+		Approximates the dominant eigenvalue and its unit eigenvector by
+		power iteration. The eigenvalue is estimated with the Rayleigh
+		quotient v^T A v, and we stop once ||A*v - lambda*v|| drops below tol.
+		The start vector is pseudo-random: a structured one such as all ones
+		is itself an eigenvector of every matrix with constant row sums, and
+		iterating from it would stop at once at the wrong eigenpair. A fixed
+		seed keeps the result the same from one call to the next.
+	*/
+	pub fn power_iteration(&self, max_iter: usize, tol: f64) -> Result<(f64, Vec<f64>), MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		let n       = self.n as usize;
+		let mut rng = StdRng::seed_from_u64(0x5eed);
+		let start   = (0..n).map(|_| rng.gen_range(0.5f64..1.5f64)).collect::<Vec<_>>();
+		let len     = norm(&start);
+		let mut v   = start.iter().map(|entry| entry / len).collect::<Vec<_>>();
+
+		for _ in 0..max_iter
+		{
+			let av     = self.mul_vec(&v)?;
+			let lambda = dot(&v, &av);
+
+			let res = av.iter()
+						.zip(v.iter())
+						.map(|(l, r)| (l - lambda * r).powi(2))
+						.sum::<f64>()
+						.sqrt();
+
+			if res < tol
+			{
+				return Ok((lambda, v));
+			}
+
+			let len = norm(&av);
+
+			if len == 0f64
+			{
+				break;
+			}
+
+			v = av.iter().map(|entry| entry / len).collect::<Vec<_>>();
+		}
+
+		Err(MatrixError::DidNotConverge { iterations: max_iter })
+	}
+
+	/*
+		Creates an n x n matrix whose entries within `lower` diagonals below
+		and `upper` diagonals above the main diagonal are set to fill, with
+		zeros elsewhere. For example lower = upper = 1 gives a tridiagonal matrix.
+	*/
+	pub fn banded(n: u32, lower: u32, upper: u32, fill: f64) -> Matrix
+	{
+		/*
+			Comparing distances from the diagonal rather than sums such as
+			j + lower keeps bands as wide as u32::MAX from overflowing.
+		*/
+		Matrix::from_fn(n, n, |i, j| {
+			let within = if i >= j { i - j <= lower } else { j - i <= upper };
+			if within { fill } else { 0f64 }
+		})
+	}
+
+	/*
+		Returns a mask of the same shape holding 1 where the entry
+		is greater than threshold and 0 elsewhere.
+	*/
+	pub fn gt_mask(&self, threshold: f64) -> Matrix
+	{
+		self.map(|entry| if entry > threshold { 1f64 } else { 0f64 })
+	}
+
+	/*
+		Returns a mask of the same shape holding 1 where the entry
+		is less than threshold and 0 elsewhere.
+	*/
+	pub fn lt_mask(&self, threshold: f64) -> Matrix
+	{
+		self.map(|entry| if entry < threshold { 1f64 } else { 0f64 })
+	}
+
+	/*
+		Returns the symmetric part (A + A^T) / 2 of a square matrix.
+	*/
+	pub fn symmetric_part(&self) -> Result<Matrix, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		let t = self.transpose();
+
+		Ok(self.map_indexed(|i, j, entry| (entry + t.entries[t.get_index(i, j)]) / 2f64))
+	}
+
+	/*
+		Returns the antisymmetric part (A - A^T) / 2 of a square matrix.
+		Added to the symmetric part it reconstructs A.
+	*/
+	pub fn antisymmetric_part(&self) -> Result<Matrix, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		let t = self.transpose();
+
+		Ok(self.map_indexed(|i, j, entry| (entry - t.entries[t.get_index(i, j)]) / 2f64))
+	}
+
+	/*
+		Multiplies every entry by scalar in place, without allocating.
+	*/
+	pub fn scale_assign(&mut self, scalar: f64)
+	{
+		self.invalidate();
+
+		for entry in self.entries.iter_mut()
+		{
+			*entry *= scalar;
+		}
+	}
+
+	/*
+		Adds other to the matrix in place, without allocating.
+	*/
+	pub fn add_assign(&mut self, other: &Matrix) -> Result<(), MatrixError>
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, self.n), found: (other.m, other.n) });
+		}
+
+		self.invalidate();
+
+		for (l, r) in self.entries.iter_mut().zip(other.entries.iter())
+		{
+			*l += r;
+		}
+
+		Ok(())
+	}
+
+	/*
+		Classifies the shape of the matrix. The more specific categories
+		win, so a 1x1 matrix is a scalar rather than a square matrix and
+		a 1xn matrix is a row vector rather than a wide matrix.
+	*/
+	pub fn shape_info(&self) -> ShapeInfo
+	{
+		match (self.m, self.n)
+		{
+			(0, _) | (_, 0)  => ShapeInfo::Empty,
+			(1, 1)           => ShapeInfo::Scalar,
+			(1, _)           => ShapeInfo::RowVector,
+			(_, 1)           => ShapeInfo::ColumnVector,
+			(m, n) if m == n => ShapeInfo::Square,
+			(m, n) if m > n  => ShapeInfo::Tall,
+			_                => ShapeInfo::Wide,
+		}
+	}
+
+	/*
+		Computes the thin singular value decomposition A = U * diag(S) * V^T
+		using one-sided Jacobi rotations. For an m x n matrix with k = min(m, n),
+		U is m x k, V is n x k and the k singular values are sorted descending.
+		U has orthonormal columns even for rank-deficient input, where the
+		columns for zero singular values complete an orthonormal basis.
+	*/
+	pub fn svd(&self) -> Result<(Matrix, Vec<f64>, Matrix), MatrixError>
+	{
+		/*
+			The rotations orthogonalize columns, so for wide matrices we
+			decompose A^T = V S U^T instead and swap the factors.
+		*/
+		if self.m < self.n
+		{
+			let (u, s, v) = self.transpose().svd()?;
+			return Ok((v, s, u));
+		}
+
+		let (m, n)        = (self.m, self.n);
+		let mut u         = self.clone();
+		let mut v         = Matrix::identity(n);
+		let mut converged = n < 2;
+		let negligible    = f64::EPSILON * f64::EPSILON * dot(&self.entries, &self.entries);
+
+		for _ in 0..JACOBI_MAX_SWEEPS
+		{
+			if converged
+			{
+				break;
+			}
+
+			converged = true;
+
+			for p in 0..n
+			{
+				for q in (p + 1)..n
+				{
+					let (mut alpha, mut beta, mut gamma) = (0f64, 0f64, 0f64);
+
+					for i in 0..m
+					{
+						let up = u.entries[u.get_index(i, p)];
+						let uq = u.entries[u.get_index(i, q)];
+
+						alpha += up * up;
+						beta  += uq * uq;
+						gamma += up * uq;
+					}
+
+					/*
+						Columns are skipped once they are orthogonal to working
+						precision, or once one of them has shrunk to rounding
+						noise relative to A (rank deficiency) and can't improve.
+					*/
+					if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() || alpha.min(beta) <= negligible
+					{
+						continue;
+					}
+
+					converged = false;
+
+					/*
+						The rotation angle is chosen so that columns p and q
+						become orthogonal; t = tan(theta) is the smaller root.
+					*/
+					let zeta = (beta - alpha) / (2f64 * gamma);
+					let t    = zeta.signum() / (zeta.abs() + (1f64 + zeta * zeta).sqrt());
+					let c    = 1f64 / (1f64 + t * t).sqrt();
+
+					u.rotate_cols(p, q, c, c * t);
+					v.rotate_cols(p, q, c, c * t);
+				}
+			}
+		}
+
+		if !converged
+		{
+			return Err(MatrixError::DidNotConverge { iterations: JACOBI_MAX_SWEEPS });
+		}
+
+		/*
+			The columns of U are now orthogonal, and their norms are the
+			singular values. We normalize them and sort in descending order.
+		*/
+		let sigma = (0..n as usize)
+			.map(|j| norm(&u.get_col_vec(j)))
+			.collect::<Vec<_>>();
+
+		let mut order = (0..n as usize).collect::<Vec<_>>();
+		order.sort_by(|&a, &b| sigma[b].partial_cmp(&sigma[a]).unwrap_or(std::cmp::Ordering::Equal));
+
+		let mut u_sorted = Matrix::new(m, n);
+		let mut v_sorted = Matrix::new(n, n);
+
+		for (dst, &src) in order.iter().enumerate()
+		{
+			let scale = if sigma[src] > 0f64 { 1f64 / sigma[src] } else { 0f64 };
+
+			for i in 0..m
+			{
+				let idx = u_sorted.get_index(i, dst as u32);
+				u_sorted.entries[idx] = u.entries[u.get_index(i, src as u32)] * scale;
+			}
+
+			for i in 0..n
+			{
+				let idx = v_sorted.get_index(i, dst as u32);
+				v_sorted.entries[idx] = v.entries[v.get_index(i, src as u32)];
+			}
+		}
+
+		let s = order.iter().map(|&j| sigma[j]).collect::<Vec<_>>();
+
+		/*
+			Columns whose singular value is at rounding level carry no
+			direction of A: they are zero or were skipped by the rotations, so
+			they aren't orthogonal to the rest. We replace them with an
+			orthonormal extension, orthogonalizing (twice, for stability) the
+			standard basis vector least represented by the columns so far.
+		*/
+		let rank = s.iter().take_while(|&&sigma| sigma * sigma > negligible).count();
+
+		for col in rank..n as usize
+		{
+			let basis = (0..col).map(|j| u_sorted.get_col_vec(j)).collect::<Vec<_>>();
+			let best  = (0..m as usize)
+				.map(|k| {
+					let mut e = (0..m as usize).map(|i| if i == k { 1f64 } else { 0f64 }).collect::<Vec<_>>();
+
+					for _ in 0..2
+					{
+						for b in &basis
+						{
+							let proj = dot(&e, b);
+							e.iter_mut().zip(b.iter()).for_each(|(x, y)| *x -= proj * y);
+						}
+					}
+
+					e
+				})
+				.max_by(|l, r| norm(l).partial_cmp(&norm(r)).unwrap_or(std::cmp::Ordering::Equal))
+				.unwrap_or_default();
+
+			let len = norm(&best);
+
+			for i in 0..m
+			{
+				let idx = u_sorted.get_index(i, col as u32);
+				u_sorted.entries[idx] = best[i as usize] / len;
+			}
+		}
+
+		Ok((u_sorted, s, v_sorted))
+	}
+
+	/*
+		Returns the entries in column-major order, as expected by
+		Fortran-style libraries such as BLAS and LAPACK.
+	*/
+	pub fn to_col_major(&self) -> Vec<f64>
+	{
+		(0..self.n as usize)
+			.flat_map(|j| self.get_col_vec(j))
+			.collect::<Vec<_>>()
+	}
+
+	/*
+		Builds an m x n matrix from entries given in column-major order.
+		This is the inverse of to_col_major.
+	*/
+	pub fn from_col_major(m: u32, n: u32, data: &[f64]) -> Result<Matrix, MatrixError>
+	{
+		let expected = (m as usize).checked_mul(n as usize);
+
+		if expected != Some(data.len())
+		{
+			return Err(MatrixError::InvalidLength { expected: expected.unwrap_or(usize::MAX), found: data.len() });
+		}
+
+		let mut res = Matrix::new(m, n);
+
+		for (idx, entry) in data.iter().enumerate()
+		{
+			let (col, row) = (idx as u32 / m, idx as u32 % m);
+			let dst        = res.get_index(row, col);
+			res.entries[dst] = *entry;
+		}
+
+		Ok(res)
+	}
+
+	/*
+		Sets every entry whose magnitude is below tol to exactly zero,
+		cleaning up rounding residue left by floating point operations.
+	*/
+	pub fn chop(&self, tol: f64) -> Matrix
+	{
+		self.map(|entry| if entry.abs() < tol { 0f64 } else { entry })
+	}
+
+	/*
+		Returns the sum of the diagonal entries of a square matrix.
+	*/
+	pub fn trace(&self) -> Result<f64, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		Ok((0..self.n).map(|i| self.entries[self.get_index(i, i)]).sum())
+	}
+
+	/*
+		Scales the matrix so that its trace becomes 1.
+	*/
+	pub fn normalize_trace(&self) -> Result<Matrix, MatrixError>
+	{
+		let trace = self.trace()?;
+
+		/*
+			The trace is only negligible next to the diagonal entries that
+			cancelled to produce it, so we compare it with sum |a_ii|.
+		*/
+		let scale = (0..self.n).map(|i| self.entries[self.get_index(i, i)].abs()).sum::<f64>();
+
+		if trace.abs() <= DEFAULT_TOL * scale
+		{
+			return Err(MatrixError::ZeroTrace);
+		}
+
+		Ok(self.map(|entry| entry / trace))
+	}
+
+	/*
+		Scales the matrix so that its determinant becomes 1, dividing by
+		the n-th root of the determinant. A negative determinant can only
+		be normalized for odd n, where the real root keeps its sign.
+	*/
+	pub fn normalize_det(&self) -> Result<Matrix, MatrixError>
+	{
+		/*
+			The determinant scales like s^n, so instead of comparing it with a
+			fixed tolerance we zero it when a pivot is negligible, as det_sign
+			does.
+		*/
+		let det = self.det_with_tol(self.pivot_tol())?;
+
+		if det == 0f64
+		{
+			return Err(MatrixError::Singular);
+		}
+
+		if det < 0f64 && self.n.is_multiple_of(2)
+		{
+			return Err(MatrixError::NegativeDeterminant);
+		}
+
+		let root = det.signum() * det.abs().powf(1f64 / self.n as f64);
+
+		Ok(self.map(|entry| entry / root))
+	}
+
+	/*
+		Builds a matrix from a list of rows, which must all have the same length.
+	*/
+	pub fn from_rows(rows: Vec<Vec<f64>>) -> Result<Matrix, MatrixError>
+	{
+		let mut builder = MatrixBuilder::new();
+
+		for row in rows
+		{
+			builder.push_row(row)?;
+		}
+
+		Ok(builder.build())
+	}
+
+	/*
+		Returns the rows of the matrix as nested vectors.
+		This is the inverse of from_rows.
+	*/
+	pub fn to_nested(&self) -> Vec<Vec<f64>>
+	{
+		(0..self.m as usize)
+			.map(|i| self.get_row_vec(i))
+			.collect::<Vec<_>>()
+	}
+
+	/*
+		Cheaply checks whether the matrix is square with a determinant
+		of magnitude greater than tol, without computing the inverse.
+	*/
+	pub fn is_invertible(&self, tol: f64) -> bool
+	{
+		match self.lu_det()
+		{
+			Ok(det) => det.abs() > tol,
+			Err(_)  => false,
+		}
+	}
+
+	/*
+		Formats the matrix according to config, one row per line.
+	*/
+	pub fn format_with(&self, config: &DisplayConfig) -> String
+	{
+		let mut out = String::new();
+
+		for i in 0..self.m as usize
+		{
+			let row = self.get_row_vec(i)
+						  .iter()
+						  .map(|entry| config.format_entry(*entry))
+						  .collect::<Vec<_>>();
+
+			out.push_str(&row.join(&config.separator));
+			out.push('\n');
+		}
+
+		out
+	}
+
+	/*
+		Sums the entries along the given axis. Axis::Rows sums every column
+		into a 1 x n matrix, Axis::Cols sums every row into an m x 1 matrix.
+	*/
+	pub fn sum_axis(&self, axis: Axis) -> Matrix
+	{
+		match axis
+		{
+			Axis::Rows => Matrix
+			{
+				m: 1,
+				n: self.n,
+				entries: (0..self.n as usize)
+							 .map(|j| self.get_col_vec(j).iter().sum())
+							 .collect::<Vec<_>>(),
+				det_cache: OnceCell::new(),
+			},
+			Axis::Cols => Matrix
+			{
+				m: self.m,
+				n: 1,
+				entries: (0..self.m as usize)
+							 .map(|i| self.get_row_vec(i).iter().sum())
+							 .collect::<Vec<_>>(),
+				det_cache: OnceCell::new(),
+			},
+		}
+	}
+
+	/*
+		Returns the coordinates of the entry preferred by better(entry, best),
+		keeping the first one on ties, or None for an empty matrix.
+	*/
+	fn arg_extreme<F: Fn(f64, f64) -> bool>(&self, better: F) -> Option<(u32, u32)>
+	{
+		let mut best: Option<usize> = None;
+
+		for (idx, entry) in self.entries.iter().enumerate()
+		{
+			if best.is_none_or(|b| better(*entry, self.entries[b]))
+			{
+				best = Some(idx);
+			}
+		}
+
+		best.map(|idx| self.get_coords(idx))
+	}
+
+	/*
+		Returns the coordinates of the largest entry, or None if the matrix is empty.
+	*/
+	pub fn argmax(&self) -> Option<(u32, u32)>
+	{
+		self.arg_extreme(|entry, best| entry > best)
+	}
+
+	/*
+		Returns the coordinates of the smallest entry, or None if the matrix is empty.
+	*/
+	pub fn argmin(&self) -> Option<(u32, u32)>
+	{
+		self.arg_extreme(|entry, best| entry < best)
+	}
+
+	/*
+		Checks whether every entry lies within tol of zero.
+	*/
+	pub fn is_zero(&self, tol: f64) -> bool
+	{
+		self.entries.iter().all(|entry| entry.abs() <= tol)
+	}
+
+	/*
+		Checks whether the matrix is square with every diagonal entry
+		within tol of one and every other entry within tol of zero.
+	*/
+	pub fn is_identity(&self, tol: f64) -> bool
+	{
+		if self.m != self.n
+		{
+			return false;
+		}
+
+		self.entries
+			.iter()
+			.enumerate()
+			.all(|(idx, entry)| {
+				let (row, col) = self.get_coords(idx);
+				let expected   = if row == col { 1f64 } else { 0f64 };
+				(entry - expected).abs() <= tol
+			})
+	}
+
+	/*
+		Iterates over the entries whose magnitude exceeds tol,
+		yielding (row, column, value) in row-major order.
+	*/
+	pub fn iter_nonzero(&self, tol: f64) -> impl Iterator<Item = (u32, u32, f64)> + '_
+	{
+		self.entries
+			.iter()
+			.enumerate()
+			.filter(move |&(_, entry)| entry.abs() > tol)
+			.map(|(idx, entry)| {
+				let (row, col) = self.get_coords(idx);
+				(row, col, *entry)
+			})
+	}
+
+	/*
+		Builds the 3x3 rotation by angle (in radians) about axis using
+		Rodrigues' formula R = I + sin(angle) K + (1 - cos(angle)) K^2,
+		where K is the cross-product matrix of the normalized axis.
+	*/
+	pub fn rotation_axis_angle(axis: &[f64], angle: f64) -> Result<Matrix, MatrixError>
+	{
+		let len = norm(axis);
+		let k   = Matrix::skew_symmetric_3d(axis)?;
+
+		if len == 0f64
+		{
+			return Err(MatrixError::ZeroVector);
+		}
+
+		let k  = k.map(|entry| entry / len);
+		let k2 = k.mul(&k);
+		let (sin, cos) = angle.sin_cos();
+
+		Ok(Matrix::identity(3).map_indexed(|i, j, entry| {
+			let idx = k.get_index(i, j);
+			entry + sin * k.entries[idx] + (1f64 - cos) * k2.entries[idx]
+		}))
+	}
+
+	/*
+		Formats the matrix as a LaTeX bmatrix environment.
+	*/
+	pub fn to_latex(&self) -> String
+	{
+		let rows = (0..self.m as usize)
+			.map(|i| {
+				self.get_row_vec(i)
+					.iter()
+					.map(|entry| entry.to_string())
+					.collect::<Vec<_>>()
+					.join(" & ")
+			})
+			.collect::<Vec<_>>();
+
+		format!("\\begin{{bmatrix}}\n{}\n\\end{{bmatrix}}", rows.join(" \\\\\n"))
+	}
+
+	/*
+		Solves the square system A*x = b using an LU factorization.
+	*/
+	pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError>
+	{
+		if b.len() != self.m as usize
+		{
+			return Err(MatrixError::InvalidLength { expected: self.m as usize, found: b.len() });
+		}
+
+		let (lu, perm) = self.lu_for_solve()?;
+
+		Ok(Matrix::lu_substitute(&lu, &perm, b))
+	}
+
+	/*
+		Solves A*X = B, treating every column of B as a right-hand side.
+		A is factored only once and the factors are reused for each column.
+	*/
+	pub fn solve_many(&self, b: &Matrix) -> Result<Matrix, MatrixError>
+	{
+		if b.m != self.m
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, b.n), found: (b.m, b.n) });
+		}
+
+		let (lu, perm) = self.lu_for_solve()?;
+		let mut res    = Matrix::new(b.m, b.n);
+
+		for j in 0..b.n
+		{
+			let x = Matrix::lu_substitute(&lu, &perm, &b.get_col_vec(j as usize));
+
+			for (i, entry) in x.iter().enumerate()
+			{
+				let idx = res.get_index(i as u32, j);
+				res.entries[idx] = *entry;
+			}
+		}
+
+		Ok(res)
+	}
+
+	/*
+		Relabels the entries into an m x n matrix with the same number of
+		entries. With Order::RowMajor the entries are read and written row by
+		row; with Order::ColMajor they are read and written column by column.
+	*/
+	pub fn reshape_order(&self, m: u32, n: u32, order: Order) -> Result<Matrix, MatrixError>
+	{
+		if m as usize * n as usize != self.entries.len()
+		{
+			return Err(MatrixError::InvalidLength { expected: self.entries.len(), found: m as usize * n as usize });
+		}
+
+		match order
+		{
+			Order::RowMajor => Matrix::from_parts(m, n, self.entries.clone()),
+			Order::ColMajor => Matrix::from_col_major(m, n, &self.to_col_major()),
+		}
+	}
+
+	/*
+		Computes the determinant by cofactor expansion like det, but returns
+		an error instead of panicking on non-square input or running
+		indefinitely on matrices larger than COFACTOR_LIMIT.
+	*/
+	pub fn try_det(&self) -> Result<f64, MatrixError>
+	{
+		self.try_det_with_limit(COFACTOR_LIMIT)
+	}
+
+	/*
+		Like try_det, with a custom limit on the size of the matrix.
+	*/
+	pub fn try_det_with_limit(&self, limit: u32) -> Result<f64, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		if self.m > limit
+		{
+			return Err(MatrixError::TooLargeForCofactor { m: self.m });
+		}
+
+		Ok(self.det())
+	}
+
+	/*
+		Builds the symmetric matrix of pairwise dot products of the given
+		vectors, computing only the upper triangle and mirroring it.
+	*/
+	fn gram_of(vectors: &[Vec<f64>]) -> Matrix
+	{
+		let k       = vectors.len() as u32;
+		let mut res = Matrix::new(k, k);
+
+		for p in 0..k
+		{
+			for q in p..k
+			{
+				let val = dot(&vectors[p as usize], &vectors[q as usize]);
+				let (upper, lower) = (res.get_index(p, q), res.get_index(q, p));
+
+				res.entries[upper] = val;
+				res.entries[lower] = val;
+			}
+		}
+
+		res
+	}
+
+	/*
+		Computes the n x n Gram matrix A^T * A without forming the transpose.
+	*/
+	pub fn gram(&self) -> Matrix
+	{
+		let cols = (0..self.n as usize).map(|j| self.get_col_vec(j)).collect::<Vec<_>>();
+		Matrix::gram_of(&cols)
+	}
+
+	/*
+		Computes the m x m Gram matrix A * A^T without forming the transpose.
+	*/
+	pub fn gram_outer(&self) -> Matrix
+	{
+		Matrix::gram_of(&self.to_nested())
+	}
+
+	/*
+		Separates a packed LU matrix, as stored by LAPACK's getrf, into its
+		factors. For an m x n input with k = min(m, n), L is the m x k unit
+		lower triangular part below the diagonal, and U is the k x n upper
+		triangular part on and above it.
+	*/
+	pub fn split_lu(combined: &Matrix) -> (Matrix, Matrix)
+	{
+		let k = combined.m.min(combined.n);
+
+		let l = Matrix::from_fn(combined.m, k, |i, j| {
+			match i.cmp(&j)
+			{
+				std::cmp::Ordering::Greater => combined.entries[combined.get_index(i, j)],
+				std::cmp::Ordering::Equal   => 1f64,
+				std::cmp::Ordering::Less    => 0f64,
+			}
+		});
+
+		let u = Matrix::from_fn(k, combined.n, |i, j| {
+			if i <= j { combined.entries[combined.get_index(i, j)] } else { 0f64 }
+		});
+
+		(l, u)
+	}
+
+	/*
+		Estimates the numerical rank as the number of singular values
+		greater than tol times the largest singular value, failing only if
+		the decomposition itself does not converge.
+	*/
+	pub fn rank_svd(&self, tol: f64) -> Result<u32, MatrixError>
+	{
+		let (_, s, _) = self.svd()?;
+		let threshold = tol * s.first().copied().unwrap_or(0f64);
+
+		Ok(s.iter().filter(|&&sigma| sigma > threshold).count() as u32)
+	}
+
+	/*
+		Reads a whitespace-delimited numeric grid, one row per line, without
+		loading the whole input into memory first. Blank lines are skipped and
+		the first row determines the number of columns.
+	*/
+	pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Matrix, MatrixError>
+	{
+		let mut builder = MatrixBuilder::new();
+
+		for (line_idx, line) in std::io::BufReader::new(reader).lines().enumerate()
+		{
+			let line = line.map_err(|err| MatrixError::Io(err.to_string()))?;
+
+			if line.trim().is_empty()
+			{
+				continue;
+			}
+
+			let row = line.split_whitespace()
+						  .map(|token| {
+							  token.parse::<f64>().map_err(|_| MatrixError::Parse {
+								  line: line_idx + 1,
+								  token: String::from(token),
+							  })
+						  })
+						  .collect::<Result<Vec<_>, _>>()?;
+
+			builder.push_row(row)?;
+		}
+
+		Ok(builder.build())
+	}
+
+	/*
+		Computes the inverse, multiplies it back and checks that the
+		product is within tol of the identity. Non-square and singular
+		matrices are reported as errors by the inversion itself.
+	*/
+	pub fn verify_inverse(&self, tol: f64) -> Result<bool, MatrixError>
+	{
+		let inv = self.inverse()?;
+
+		Ok(self.mul(&inv).is_identity(tol))
+	}
+
+	/*
+		Applies a single elementary row operation in place.
+	*/
+	pub fn apply_row_op(&mut self, op: &RowOp)
+	{
+		self.invalidate();
+
+		match *op
+		{
+			RowOp::Swap(a, b) => self.swap_rows(a, b),
+			RowOp::Scale { row, factor } => {
+				for j in 0..self.n
+				{
+					let idx = self.get_index(row, j);
+					self.entries[idx] *= factor;
+				}
+			}
+			RowOp::AddScaled { target, source, factor } => {
+				for j in 0..self.n
+				{
+					let (dst, src) = (self.get_index(target, j), self.get_index(source, j));
+					self.entries[dst] += factor * self.entries[src];
+				}
+			}
+		}
+	}
+
+	/*
+		Reduces the matrix to reduced row echelon form by Gauss-Jordan
+		elimination with partial pivoting, treating entries with magnitude
+		at most tol as zero. Returns the reduced matrix, the row operations
+		that produced it and the indices of the pivot columns.
+	*/
+	fn row_reduce(&self, tol: f64) -> (Matrix, Vec<RowOp>, Vec<u32>)
+	{
+		let mut a      = self.clone();
+		let mut ops    = Vec::new();
+		let mut pivots = Vec::new();
+		let mut row    = 0;
+
+		let mut apply = |a: &mut Matrix, op: RowOp| {
+			a.apply_row_op(&op);
+			ops.push(op);
+		};
+
+		for col in 0..self.n
+		{
+			if row == self.m
+			{
+				break;
+			}
+
+			let mut pivot = row;
+
+			for i in (row + 1)..self.m
+			{
+				if a.entries[a.get_index(i, col)].abs() > a.entries[a.get_index(pivot, col)].abs()
+				{
+					pivot = i;
+				}
+			}
+
+			let value = a.entries[a.get_index(pivot, col)];
+
+			if value.abs() <= tol
+			{
+				continue;
+			}
+
+			if pivot != row
+			{
+				apply(&mut a, RowOp::Swap(row, pivot));
+			}
+
+			if value != 1f64
+			{
+				apply(&mut a, RowOp::Scale { row, factor: 1f64 / value });
+			}
+
+			for i in (0..self.m).filter(|&i| i != row)
+			{
+				let factor = a.entries[a.get_index(i, col)];
+
+				if factor != 0f64
+				{
+					apply(&mut a, RowOp::AddScaled { target: i, source: row, factor: -factor });
+				}
+			}
+
+			pivots.push(col);
+			row += 1;
+		}
+
+		(a, ops, pivots)
+	}
+
+	/*
+		Returns the reduced row echelon form of the matrix.
+	*/
+	pub fn rref(&self) -> Matrix
+	{
+		self.row_reduce(DEFAULT_TOL).0
+	}
+
+	/*
+		Like rref, but also returns the elementary row operations in the
+		order they were applied, so the reduction can be replayed step by
+		step with apply_row_op.
+	*/
+	pub fn rref_steps(&self) -> (Matrix, Vec<RowOp>)
+	{
+		let (res, ops, _) = self.row_reduce(DEFAULT_TOL);
+		(res, ops)
+	}
+
+	/*
+		Applies the matrix to every column of x, returning the matrix whose
+		k-th column is A * x_k. x must have as many rows as A has columns.
+	*/
+	pub fn apply_columns(&self, x: &Matrix) -> Result<Matrix, MatrixError>
+	{
+		if x.m != self.n
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.n, x.n), found: (x.m, x.n) });
+		}
+
+		Ok(self.mul(x))
+	}
+
+	/*
+		Computes the eigenvalues and eigenvectors of a symmetric matrix with
+		the cyclic Jacobi eigenvalue algorithm. The eigenvalues are returned
+		in descending order, and the corresponding unit eigenvectors are the
+		columns of the returned orthogonal matrix.
+	*/
+	pub fn symmetric_eig(&self) -> Result<(Vec<f64>, Matrix), MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		if !self.is_symmetric()
+		{
+			return Err(MatrixError::NotSymmetric);
+		}
+
+		let n          = self.n;
+		let mut a      = self.clone();
+		let mut v      = Matrix::identity(n);
+		let negligible = f64::EPSILON * f64::EPSILON * dot(&self.entries, &self.entries);
+		let mut converged = false;
+
+		for _ in 0..JACOBI_MAX_SWEEPS
+		{
+			let off = a.entries
+					   .iter()
+					   .enumerate()
+					   .filter(|&(idx, _)| { let (row, col) = a.get_coords(idx); row != col })
+					   .map(|(_, entry)| entry * entry)
+					   .sum::<f64>();
+
+			if off <= negligible
+			{
+				converged = true;
+				break;
+			}
+
+			for p in 0..n
+			{
+				for q in (p + 1)..n
+				{
+					let apq = a.entries[a.get_index(p, q)];
+
+					if apq == 0f64
+					{
+						continue;
+					}
+
+					/*
+						Each rotation A' = J^T A J annihilates the entries
+						(p, q) and (q, p). Accumulating V = V J collects
+						the eigenvectors in the columns of V.
+					*/
+					let (app, aqq) = (a.entries[a.get_index(p, p)], a.entries[a.get_index(q, q)]);
+					let theta = (aqq - app) / (2f64 * apq);
+					let t     = theta.signum() / (theta.abs() + (theta * theta + 1f64).sqrt());
+					let c     = 1f64 / (t * t + 1f64).sqrt();
+
+					a.rotate_cols(p, q, c, c * t);
+					a.rotate_rows(p, q, c, c * t);
+					v.rotate_cols(p, q, c, c * t);
+				}
+			}
+		}
+
+		if !converged
+		{
+			return Err(MatrixError::DidNotConverge { iterations: JACOBI_MAX_SWEEPS });
+		}
+
+		let values = (0..n).map(|i| a.entries[a.get_index(i, i)]).collect::<Vec<_>>();
+
+		let mut order = (0..n as usize).collect::<Vec<_>>();
+		order.sort_by(|&l, &r| values[r].partial_cmp(&values[l]).unwrap_or(std::cmp::Ordering::Equal));
+
+		let vectors = v.map_indexed(|i, j, _| v.entries[v.get_index(i, order[j as usize] as u32)]);
+
+		Ok((order.iter().map(|&k| values[k]).collect::<Vec<_>>(), vectors))
+	}
+
+	/*
+		Repeats the matrix vertical times downwards and horizontal times
+		across, producing an (m * vertical) x (n * horizontal) matrix.
+	*/
+	pub fn tile(&self, vertical: u32, horizontal: u32) -> Matrix
+	{
+		let (m, n) = match (self.m.checked_mul(vertical), self.n.checked_mul(horizontal))
+		{
+			(Some(m), Some(n)) => (m, n),
+			_ => panic!("cannot tile a matrix beyond u32 dimensions"),
+		};
+
+		Matrix::from_fn(m, n, |i, j| {
+			self.entries[self.get_index(i % self.m, j % self.n)]
+		})
+	}
+
+	/*
+		Partitions a square matrix at index k into the blocks
+		[A B]
+		[C D]
+		where A is k x k, and returns the Schur complement D - C * A^-1 * B.
+		Rather than inverting A we solve A * X = B and form D - C * X.
+	*/
+	pub fn schur_complement(&self, k: u32) -> Result<Matrix, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		if k >= self.m
+		{
+			return Err(MatrixError::OutOfBounds { index: k, bound: self.m });
+		}
+
+		let rest = self.m - k;
+		let a    = self.block(0, 0, k, k);
+		let b    = self.block(0, k, k, rest);
+		let c    = self.block(k, 0, rest, k);
+		let d    = self.block(k, k, rest, rest);
+		let cx   = c.mul(&a.solve_many(&b)?);
+
+		Ok(d.map_indexed(|i, j, entry| entry - cx.entries[cx.get_index(i, j)]))
+	}
+
+	/*
+		Folds over the entries in row-major order, starting from init.
+	*/
+	pub fn fold<B, F: Fn(B, f64) -> B>(&self, init: B, f: F) -> B
+	{
+		self.entries.iter().fold(init, |acc, entry| f(acc, *entry))
+	}
+
+	/*
+		Computes the principal square root of a symmetric positive-definite
+		matrix as V * diag(sqrt(lambda)) * V^T from its eigendecomposition.
+	*/
+	pub fn sqrt_spd(&self) -> Result<Matrix, MatrixError>
+	{
+		let (values, v) = self.symmetric_eig()?;
+
+		if values.iter().any(|&lambda| lambda < 0f64)
+		{
+			return Err(MatrixError::NotPositiveDefinite);
+		}
+
+		let scaled = v.map_indexed(|_, j, entry| entry * values[j as usize].sqrt());
+
+		Ok(scaled.mul(&v.transpose()))
+	}
+
+	/*
+		Cross-checks the symmetric eigendecomposition against two invariants:
+		the eigenvalues must sum to the trace and multiply to the determinant,
+		both within tol.
+	*/
+	pub fn eigen_invariants_ok(&self, tol: f64) -> Result<bool, MatrixError>
+	{
+		let (values, _) = self.symmetric_eig()?;
+
+		Ok(Matrix::invariants_match(&values, self.trace()?, self.lu_det()?, tol))
+	}
+
+	/*
+		Checks that eigenvalues sum to trace and multiply to det within tol.
+	*/
+	fn invariants_match(values: &[f64], trace: f64, det: f64, tol: f64) -> bool
+	{
+		let sum     = values.iter().sum::<f64>();
+		let product = values.iter().product::<f64>();
+
+		(sum - trace).abs() <= tol && (product - det).abs() <= tol
+	}
+
+	/*
+		Embeds the matrix in the top-left corner of a zero matrix whose
+		dimensions are the next powers of two. Empty dimensions stay empty.
+	*/
+	pub fn pad_to_pow2(&self) -> Matrix
+	{
+		let pow2 = |k: u32| if k == 0 { 0 } else { k.next_power_of_two() };
+
+		Matrix::from_fn(pow2(self.m), pow2(self.n), |i, j| {
+			if i < self.m && j < self.n { self.entries[self.get_index(i, j)] } else { 0f64 }
+		})
+	}
+
+	/*
+		Crops the matrix to its top-left m x n block, e.g. to undo pad_to_pow2.
+	*/
+	pub fn trim(&self, m: u32, n: u32) -> Matrix
+	{
+		if m > self.m || n > self.n
+		{
+			panic!("cannot trim a matrix to larger dimensions");
+		}
+
+		self.block(0, 0, m, n)
+	}
+
+	/*
+		Returns the indices of the pivot columns found while reducing the
+		matrix to row echelon form, treating entries at most tol as zero.
+		The remaining columns correspond to free variables.
+	*/
+	pub fn pivot_columns(&self, tol: f64) -> Vec<u32>
+	{
+		self.row_reduce(tol).2
+	}
+
+	/*
+		Finds the largest absolute difference between corresponding entries,
+		returning it with the row and column where it occurs. Empty matrices
+		have no entries to compare and are reported as EmptyInput.
+	*/
+	pub fn max_abs_diff(&self, other: &Matrix) -> Result<(f64, u32, u32), MatrixError>
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, self.n), found: (other.m, other.n) });
+		}
+
+		let diff   = self.map_indexed(|i, j, entry| (entry - other.entries[other.get_index(i, j)]).abs());
+		let (i, j) = diff.argmax().ok_or(MatrixError::EmptyInput)?;
+
+		Ok((diff.entries[diff.get_index(i, j)], i, j))
+	}
+
+	/*
+		Overwrites the main diagonal in place with values, which must
+		hold exactly min(m, n) entries. Off-diagonal entries are untouched.
+	*/
+	pub fn set_diagonal(&mut self, values: &[f64]) -> Result<(), MatrixError>
+	{
+		let k = self.m.min(self.n) as usize;
+
+		if values.len() != k
+		{
+			return Err(MatrixError::InvalidLength { expected: k, found: values.len() });
+		}
+
+		self.invalidate();
+
+		for (i, value) in values.iter().enumerate()
+		{
+			let idx = self.get_index(i as u32, i as u32);
+			self.entries[idx] = *value;
+		}
+
+		Ok(())
+	}
+
+	/*
+		Serializes the matrix in the NumPy .npy v1.0 format as a 2D
+		little-endian float64 array in C (row-major) order. The header is
+		padded with spaces so that the data starts at a multiple of 64 bytes.
+	*/
+	pub fn to_npy(&self) -> Vec<u8>
+	{
+		let mut header = format!("{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}", self.m, self.n);
+
+		/*
+			The preamble is the magic string, two version bytes
+			and two bytes for the header length.
+		*/
+		let preamble = NPY_MAGIC.len() + 4;
+
+		while !(preamble + header.len() + 1).is_multiple_of(64)
+		{
+			header.push(' ');
+		}
+
+		header.push('\n');
+
+		let mut bytes = Vec::with_capacity(preamble + header.len() + self.entries.len() * 8);
+
+		bytes.extend_from_slice(NPY_MAGIC);
+		bytes.extend_from_slice(&[1, 0]);
+		bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+		bytes.extend_from_slice(header.as_bytes());
+
+		for entry in self.entries.iter()
+		{
+			bytes.extend_from_slice(&entry.to_le_bytes());
+		}
+
+		bytes
+	}
+
+	/*
+		Parses a NumPy .npy v1.0 file holding a 2D little-endian float64
+		array in C order. Any other dtype, Fortran order or dimensionality is
+		rejected rather than silently reinterpreted.
+	*/
+	pub fn from_npy(bytes: &[u8]) -> Result<Matrix, MatrixError>
+	{
+		let invalid = |reason: &str| MatrixError::InvalidNpy(String::from(reason));
+		let preamble = NPY_MAGIC.len() + 4;
+
+		if bytes.len() < preamble || !bytes.starts_with(NPY_MAGIC)
+		{
+			return Err(invalid("missing magic string"));
+		}
+
+		if bytes[NPY_MAGIC.len()] != 1
+		{
+			return Err(invalid("unsupported format version"));
+		}
+
+		let header_len = u16::from_le_bytes([bytes[preamble - 2], bytes[preamble - 1]]) as usize;
+		let data_start = preamble + header_len;
+
+		let header = bytes.get(preamble..data_start)
+						  .and_then(|header| std::str::from_utf8(header).ok())
+						  .ok_or_else(|| invalid("truncated header"))?;
+
+		if npy_header_value(header, "descr") != Some("'<f8'")
+		{
+			return Err(invalid("dtype must be '<f8'"));
+		}
+
+		if npy_header_value(header, "fortran_order") != Some("False")
+		{
+			return Err(invalid("array must be in C order"));
+		}
+
+		let shape = npy_header_value(header, "shape")
+			.ok_or_else(|| invalid("missing shape"))?
+			.trim_matches(|c| c == '(' || c == ')')
+			.split(',')
+			.map(str::trim)
+			.filter(|dim| !dim.is_empty())
+			.map(|dim| dim.parse::<u32>().map_err(|_| invalid("malformed shape")))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		if shape.len() != 2
+		{
+			return Err(invalid("array must be two-dimensional"));
+		}
+
+		let (m, n) = (shape[0], shape[1]);
+		let data   = &bytes[data_start..];
+
+		/*
+			The shape comes from untrusted input, so the expected byte count
+			is computed with overflow checks.
+		*/
+		let expected = (m as usize)
+			.checked_mul(n as usize)
+			.and_then(|len| len.checked_mul(8))
+			.ok_or_else(|| invalid("shape too large"))?;
+
+		if data.len() != expected
+		{
+			return Err(invalid("data length does not match shape"));
+		}
+
+		let entries = data.chunks_exact(8)
+						  .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+						  .collect::<Vec<_>>();
+
+		Matrix::from_parts(m, n, entries)
+	}
+
+	/*
+		Raises every entry to exponent (element-wise, not a matrix power).
+		As with f64::powf, a negative entry with a non-integer exponent gives NaN.
+	*/
+	pub fn powf(&self, exponent: f64) -> Matrix
+	{
+		self.map(|entry| entry.powf(exponent))
+	}
+
+	/*
+		Splits a square matrix additively into its strictly lower triangular,
+		diagonal and strictly upper triangular parts, A = L + D + U, as used
+		by the Jacobi and Gauss-Seidel iterations.
+	*/
+	pub fn split_ldu(&self) -> Result<(Matrix, Matrix, Matrix), MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		let l = self.map_indexed(|i, j, entry| if i > j { entry } else { 0f64 });
+		let u = self.map_indexed(|i, j, entry| if i < j { entry } else { 0f64 });
+
+		Ok((l, self.diagonal_matrix(), u))
+	}
+
+	/*
+		Runs a stationary iterative method for A*x = b starting from x = 0.
+		Jacobi updates every component from the previous iterate, while
+		Gauss-Seidel uses each updated component as soon as it is known.
+		After every iteration callback receives the iteration number
+		(starting at 1) and the residual norm, and we stop once it is below tol.
+	*/
+	fn solve_iterative<F: FnMut(usize, f64)>(
+		&self,
+		b: &[f64],
+		max_iter: usize,
+		tol: f64,
+		gauss_seidel: bool,
+		mut callback: F,
+	) -> Result<Vec<f64>, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		if b.len() != self.m as usize
+		{
+			return Err(MatrixError::InvalidLength { expected: self.m as usize, found: b.len() });
+		}
+
+		if let Some(index) = (0..self.n).find(|&i| self.entries[self.get_index(i, i)] == 0f64)
+		{
+			return Err(MatrixError::ZeroDiagonal { index });
+		}
+
+		let mut x = vec![0f64; self.n as usize];
+
+		for iter in 1..=max_iter
+		{
+			let prev = x.clone();
+
+			for i in 0..self.n
+			{
+				let source = if gauss_seidel { &x } else { &prev };
+				let sum    = (0..self.n)
+					.filter(|&j| j != i)
+					.map(|j| self.entries[self.get_index(i, j)] * source[j as usize])
+					.sum::<f64>();
+
+				x[i as usize] = (b[i as usize] - sum) / self.entries[self.get_index(i, i)];
+			}
+
+			let res = self.residual(&x, b)?;
+			callback(iter, res);
+
+			if res < tol
+			{
+				return Ok(x);
+			}
+		}
+
+		Err(MatrixError::DidNotConverge { iterations: max_iter })
+	}
+
+	/*
+		Solves A*x = b with the Jacobi iteration, which converges for
+		strictly diagonally dominant matrices.
+	*/
+	pub fn solve_jacobi(&self, b: &[f64], max_iter: usize, tol: f64) -> Result<Vec<f64>, MatrixError>
+	{
+		self.solve_iterative(b, max_iter, tol, false, |_, _| {})
+	}
+
+	/*
+		Like solve_jacobi, calling callback with the iteration number and
+		residual norm after every iteration.
+	*/
+	pub fn solve_jacobi_with_callback<F: FnMut(usize, f64)>(&self, b: &[f64], max_iter: usize, tol: f64, callback: F) -> Result<Vec<f64>, MatrixError>
+	{
+		self.solve_iterative(b, max_iter, tol, false, callback)
+	}
+
+	/*
+		Solves A*x = b with the Gauss-Seidel iteration, which converges for
+		strictly diagonally dominant or symmetric positive-definite matrices.
+	*/
+	pub fn solve_gauss_seidel(&self, b: &[f64], max_iter: usize, tol: f64) -> Result<Vec<f64>, MatrixError>
+	{
+		self.solve_iterative(b, max_iter, tol, true, |_, _| {})
+	}
+
+	/*
+		Like solve_gauss_seidel, calling callback with the iteration number
+		and residual norm after every iteration.
+	*/
+	pub fn solve_gauss_seidel_with_callback<F: FnMut(usize, f64)>(&self, b: &[f64], max_iter: usize, tol: f64, callback: F) -> Result<Vec<f64>, MatrixError>
+	{
+		self.solve_iterative(b, max_iter, tol, true, callback)
+	}
+
+	/*
+		Householder QR with column pivoting, returning (Q, R, perm) such that
+		column j of Q*R is column perm[j] of self. At each step the remaining
+		column of largest norm is moved into place, so the magnitudes along the
+		diagonal of R are non-increasing and the numerical rank can be read off
+		as the number of diagonal entries above a tolerance.
+	*/
+	pub fn qr_pivot(&self) -> Result<(Matrix, Matrix, Vec<usize>), MatrixError>
+	{
+		if self.m == 0 || self.n == 0
+		{
+			return Err(MatrixError::EmptyInput);
+		}
+
+		let (m, n)   = (self.m, self.n);
+		let mut q    = Matrix::identity(m);
+		let mut r    = self.clone();
+		let mut perm = (0..n as usize).collect::<Vec<usize>>();
+
+		for k in 0..m.min(n)
+		{
+			let tail_norm = |r: &Matrix, j: u32| (k..m).map(|i| r.entries[r.get_index(i, j)].powi(2)).sum::<f64>();
+			let p         = (k..n).fold(k, |best, j| if tail_norm(&r, j) > tail_norm(&r, best) { j } else { best });
+
+			if p != k
+			{
+				for i in 0..m
+				{
+					let (a, b) = (r.get_index(i, k), r.get_index(i, p));
+					r.entries.swap(a, b);
+				}
+
+				perm.swap(k as usize, p as usize);
+			}
+
+			/*
+				The reflector maps x = R[k.., k] onto -sign(x_0) * ||x|| * e_0;
+				choosing the sign opposite to x_0 avoids cancellation in v_0.
+			*/
+			let mut v = (k..m).map(|i| r.entries[r.get_index(i, k)]).collect::<Vec<f64>>();
+			let alpha = -v[0].signum() * norm(&v);
+			v[0]     -= alpha;
+
+			let vv = dot(&v, &v);
+
+			if vv == 0f64
+			{
+				continue;
+			}
+
+			for j in k..n
+			{
+				let scale = 2f64 * (k..m).map(|i| v[(i - k) as usize] * r.entries[r.get_index(i, j)]).sum::<f64>() / vv;
+
+				for i in k..m
+				{
+					let idx = r.get_index(i, j);
+					r.entries[idx] -= scale * v[(i - k) as usize];
+				}
+			}
+
+			for i in 0..m
+			{
+				let scale = 2f64 * (k..m).map(|j| q.entries[q.get_index(i, j)] * v[(j - k) as usize]).sum::<f64>() / vv;
+
+				for j in k..m
+				{
+					let idx = q.get_index(i, j);
+					q.entries[idx] -= scale * v[(j - k) as usize];
+				}
+			}
+
+			for i in (k + 1)..m
+			{
+				let idx = r.get_index(i, k);
+				r.entries[idx] = 0f64;
+			}
+		}
+
+		Ok((q, r, perm))
+	}
+
+	/*
+		Returns a zero matrix with the same dimensions as self.
+	*/
+	pub fn zeros_like(&self) -> Matrix
+	{
+		Matrix::new(self.m, self.n)
+	}
+
+	/*
+		Returns a matrix of ones with the same dimensions as self.
+	*/
+	pub fn ones_like(&self) -> Matrix
+	{
+		Matrix::filled(self.m, self.n, 1f64)
+	}
+
+	/*
+		Returns the identity matrix of the same size as self, which must be
+		square.
+	*/
+	pub fn identity_like(&self) -> Result<Matrix, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		Ok(Matrix::identity(self.n))
+	}
+
+	/*
+		Returns the number of rows.
+	*/
+	pub fn rows(&self) -> u32
+	{
+		self.m
+	}
+
+	/*
+		Returns the number of columns.
+	*/
+	pub fn cols(&self) -> u32
+	{
+		self.n
+	}
+
+	/*
+		Returns the dimensions as (rows, columns).
+	*/
+	pub fn shape(&self) -> (u32, u32)
+	{
+		(self.m, self.n)
+	}
+
+	/*
+		Returns the Frobenius inner product sum_{i,j} A[i][j] * B[i][j],
+		which equals trace(A^T * B) without forming the product.
+	*/
+	pub fn frobenius_inner(&self, other: &Matrix) -> Result<f64, MatrixError>
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, self.n), found: (other.m, other.n) });
+		}
+
+		Ok(dot(&self.entries, &other.entries))
+	}
+
+	/*
+		Returns the 2m x n matrix whose rows alternate between self and other,
+		starting with self's first row. Both must have the same dimensions.
+	*/
+	pub fn interleave_rows(&self, other: &Matrix) -> Result<Matrix, MatrixError>
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, self.n), found: (other.m, other.n) });
+		}
+
+		let m = self.m.checked_mul(2).ok_or(MatrixError::DimensionOverflow)?;
+
+		Ok(Matrix::from_fn(m, self.n, |i, j| {
+			let source = if i % 2 == 0 { self } else { other };
+			source.entries[source.get_index(i / 2, j)]
+		}))
+	}
+
+	/*
+		Computes the determinant together with the LU factorization it was
+		read from, returning (det, L, U, perm) with P*A = L*U, where row i of
+		P*A is row perm[i] of A. The factors can then be reused for solving
+		without factorizing a second time.
+	*/
+	pub fn det_and_lu(&self) -> Result<(f64, Matrix, Matrix, Vec<usize>), MatrixError>
+	{
+		let (lu, perm, swaps) = self.lu_packed()?;
+		let sign = if swaps % 2 == 0 { 1f64 } else { -1f64 };
+		let det  = (0..self.n).fold(sign, |det, k| det * lu.entries[lu.get_index(k, k)]);
+		let (l, u) = Matrix::split_lu(&lu);
+
+		Ok((det, l, u, perm))
+	}
+
+	/*
+		Estimates the spectral radius as the magnitude of the dominant
+		eigenvalue found by power_iteration. Iterative methods such as
+		solve_jacobi converge when their iteration matrix has radius < 1.
+	*/
+	pub fn spectral_radius(&self, max_iter: usize, tol: f64) -> Result<f64, MatrixError>
+	{
+		let (lambda, _) = self.power_iteration(max_iter, tol)?;
+
+		Ok(lambda.abs())
+	}
+
+	/*
+		Rescales every entry linearly into [0, 1], mapping the global minimum
+		to 0 and the global maximum to 1. A constant matrix has no range to
+		divide by and is returned as all zeros.
+	*/
+	pub fn min_max_normalize(&self) -> Matrix
+	{
+		let min   = self.fold(f64::INFINITY, f64::min);
+		let max   = self.fold(f64::NEG_INFINITY, f64::max);
+		let range = max - min;
+
+		if range > 0f64
+		{
+			self.map(|entry| (entry - min) / range)
+		}
+		else
+		{
+			self.zeros_like()
+		}
+	}
+
+	/*
+		Computes the inverse as adj(A) / det(A), where the adjugate is the
+		transposed matrix of cofactors. Each entry only needs a single
+		division at the end, so integer matrices with determinant +-1 have an
+		exact inverse. The cofactor expansion is limited to COFACTOR_LIMIT.
+		Singularity is decided by det_sign, which applies the same relative
+		pivot test as inverse, and det(A) is then expanded along the first
+		row from the cofactors.
+	*/
+	pub fn inverse_adjugate(&self) -> Result<Matrix, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		if self.n > COFACTOR_LIMIT
+		{
+			return Err(MatrixError::TooLargeForCofactor { m: self.n });
+		}
+
+		if self.det_sign()? == 0
+		{
+			return Err(MatrixError::Singular);
+		}
+
+		let cofactors = Matrix::from_fn(self.n, self.n, |i, j| {
+			let sign = if (i + j) % 2 == 0 { 1f64 } else { -1f64 };
+			sign * self.sub_matrix(i, j).det()
+		});
+
+		let det = (0..self.n)
+			.map(|j| self.entries[self.get_index(0, j)] * cofactors.entries[cofactors.get_index(0, j)])
+			.sum::<f64>();
+
+		Ok(cofactors.transpose().map(|entry| entry / det))
+	}
+
+	/*
+		Builds a matrix from a list of columns, which must all have the same
+		length. This is the column-oriented counterpart of from_rows.
+	*/
+	pub fn from_columns(cols: Vec<Vec<f64>>) -> Result<Matrix, MatrixError>
+	{
+		let m = cols.first().map_or(0, |col| col.len());
+
+		if let Some(col) = cols.iter().find(|col| col.len() != m)
+		{
+			return Err(MatrixError::InvalidLength { expected: m, found: col.len() });
+		}
+
+		Ok(Matrix::from_fn(m as u32, cols.len() as u32, |i, j| cols[j as usize][i as usize]))
+	}
+
+	/*
+		Returns how many row interchanges partial pivoting performed while
+		factorizing the matrix, as used for the sign of the determinant.
+	*/
+	pub fn lu_swap_count(&self) -> Result<usize, MatrixError>
+	{
+		let (_, _, swaps) = self.lu_packed()?;
+
+		Ok(swaps)
+	}
+
+	/*
+		Returns the entrywise maximum of two equally-shaped matrices.
+	*/
+	pub fn elementwise_max(&self, other: &Matrix) -> Result<Matrix, MatrixError>
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, self.n), found: (other.m, other.n) });
+		}
+
+		Ok(self.map_indexed(|i, j, entry| entry.max(other.entries[other.get_index(i, j)])))
+	}
+
+	/*
+		Returns the entrywise minimum of two equally-shaped matrices.
+	*/
+	pub fn elementwise_min(&self, other: &Matrix) -> Result<Matrix, MatrixError>
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, self.n), found: (other.m, other.n) });
+		}
+
+		Ok(self.map_indexed(|i, j, entry| entry.min(other.entries[other.get_index(i, j)])))
+	}
+
+	/*
+		Computes the summed-area table, where entry (i, j) is the sum of all
+		entries in the rectangle from (0, 0) to (i, j) inclusive. Any rectangle
+		sum can then be read off from four table entries.
+	*/
+	pub fn integral_image(&self) -> Matrix
+	{
+		let mut res = self.clone();
+
+		for i in 0..self.m
+		{
+			for j in 0..self.n
+			{
+				let up   = if i > 0 { res.entries[res.get_index(i - 1, j)] } else { 0f64 };
+				let left = if j > 0 { res.entries[res.get_index(i, j - 1)] } else { 0f64 };
+				let diag = if i > 0 && j > 0 { res.entries[res.get_index(i - 1, j - 1)] } else { 0f64 };
+
+				let idx = res.get_index(i, j);
+				res.entries[idx] += up + left - diag;
+			}
+		}
+
+		res
+	}
+
+	/*
+		Returns every pair of row indices (i, k) with i < k whose rows lie
+		within Euclidean distance tol of each other.
+	*/
+	pub fn duplicate_rows(&self, tol: f64) -> Vec<(u32, u32)>
+	{
+		let rows = (0..self.m as usize).map(|i| self.get_row_vec(i)).collect::<Vec<_>>();
+		let mut res = Vec::new();
+
+		for i in 0..self.m
+		{
+			for k in (i + 1)..self.m
+			{
+				if distance(&rows[i as usize], &rows[k as usize]) <= tol
+				{
+					res.push((i, k));
+				}
+			}
+		}
+
+		res
+	}
+
+	/*
+		Classifies the system A*x = b by comparing the rank of A with that of
+		the augmented matrix [A | b] (Rouche-Capelli): a larger augmented rank
+		means b is outside the column space, equal ranks of n mean the solution
+		is unique, and anything less leaves free variables.
+	*/
+	pub fn solvability(&self, b: &[f64]) -> Result<Solvability, MatrixError>
+	{
+		if b.len() != self.m as usize
+		{
+			return Err(MatrixError::InvalidLength { expected: self.m as usize, found: b.len() });
+		}
+
+		let augmented = Matrix::from_fn(self.m, self.n + 1, |i, j| {
+			if j < self.n { self.entries[self.get_index(i, j)] } else { b[i as usize] }
+		});
+
+		/*
+			A pivot is negligible relative to the entries it was eliminated
+			from, so each rank uses a tolerance scaled by its own matrix: b may
+			be far larger or smaller than the entries of A.
+		*/
+		let rank           = self.pivot_columns(DEFAULT_TOL * self.max_abs()).len();
+		let augmented_rank = augmented.pivot_columns(DEFAULT_TOL * augmented.max_abs()).len();
+
+		if augmented_rank > rank
+		{
+			Ok(Solvability::NoSolution)
+		}
+		else if rank == self.n as usize
+		{
+			Ok(Solvability::UniqueSolution)
+		}
+		else
+		{
+			Ok(Solvability::InfiniteSolutions)
+		}
+	}
+
+	/*
+		Computes trace(A^k), the k-th power sum of the eigenvalues, with
+		k == 0 giving trace(I) = n. Only A^(k-1) is formed: the final product
+		contributes just its diagonal, which is the Frobenius inner product
+		of A^(k-1) with A^T.
+	*/
+	pub fn trace_of_power(&self, k: u32) -> Result<f64, MatrixError>
+	{
+		if self.m != self.n
+		{
+			return Err(MatrixError::NotSquare { m: self.m, n: self.n });
+		}
+
+		if k == 0
+		{
+			return Ok(self.n as f64);
+		}
+
+		let mut power = Matrix::identity(self.n);
+
+		for _ in 1..k
+		{
+			power = power.mul(self);
+		}
+
+		power.frobenius_inner(&self.transpose())
+	}
+
+	/*
+		Returns the matrix with its columns in reverse order.
+	*/
+	pub fn flip_horizontal(&self) -> Matrix
+	{
+		Matrix::from_fn(self.m, self.n, |i, j| self.entries[self.get_index(i, self.n - 1 - j)])
+	}
+
+	/*
+		Returns the matrix with its rows in reverse order.
+	*/
+	pub fn flip_vertical(&self) -> Matrix
+	{
+		Matrix::from_fn(self.m, self.n, |i, j| self.entries[self.get_index(self.m - 1 - i, j)])
+	}
+
+	/*
+		Rotates the matrix clockwise by 90 degrees `times` times. A single
+		clockwise turn is a transpose followed by reversing the columns, so
+		odd rotations swap the dimensions.
+	*/
+	pub fn rot90(&self, times: u32) -> Matrix
+	{
+		match times % 4
+		{
+			0 => self.clone(),
+			1 => self.transpose().flip_horizontal(),
+			2 => self.flip_vertical().flip_horizontal(),
+			_ => self.transpose().flip_vertical(),
+		}
+	}
+
+	/*
+		Computes alpha * (A * B) + beta * C in the manner of BLAS's GEMM,
+		writing each product entry straight into a copy of C rather than
+		forming the intermediate product and scaled matrices.
+	*/
+	pub fn gemm(&self, b: &Matrix, c: &Matrix, alpha: f64, beta: f64) -> Result<Matrix, MatrixError>
+	{
+		if self.n != b.m
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.n, b.n), found: (b.m, b.n) });
+		}
+
+		if (c.m, c.n) != (self.m, b.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (self.m, b.n), found: (c.m, c.n) });
+		}
+
+		let cols    = (0..b.n as usize).map(|j| b.get_col_vec(j)).collect::<Vec<_>>();
+		let mut res = c.clone();
+
+		for i in 0..self.m
+		{
+			let row_vec = self.get_row_vec(i as usize);
+
+			for j in 0..b.n
+			{
+				let idx = res.get_index(i, j);
+				res.entries[idx] = alpha * dot(&row_vec, &cols[j as usize]) + beta * res.entries[idx];
+			}
+		}
+
+		Ok(res)
+	}
+}
+
+impl DisplayConfig
+{
+	/*
+		Creates the default configuration, which matches Display:
+		shortest representation, no exponent, entries separated by a space.
+	*/
+	pub fn new() -> DisplayConfig
+	{
+		DisplayConfig
+		{
+			precision: None,
+			scientific: false,
+			separator: String::from(" "),
+		}
+	}
+
+	/*
+		Sets the number of digits printed after the decimal point.
+	*/
+	pub fn precision(mut self, digits: usize) -> DisplayConfig
+	{
+		self.precision = Some(digits);
+		self
+	}
+
+	/*
+		Selects scientific notation, e.g. 1.5e3 instead of 1500.
+	*/
+	pub fn scientific(mut self, enabled: bool) -> DisplayConfig
+	{
+		self.scientific = enabled;
+		self
+	}
+
+	/*
+		Sets the string placed between entries of a row.
+	*/
+	pub fn separator(mut self, separator: &str) -> DisplayConfig
+	{
+		self.separator = String::from(separator);
+		self
+	}
+
+	fn format_entry(&self, entry: f64) -> String
+	{
+		match (self.precision, self.scientific)
+		{
+			(Some(digits), true)  => format!("{:.*e}", digits, entry),
+			(None, true)          => format!("{:e}", entry),
+			(Some(digits), false) => format!("{:.*}", digits, entry),
+			(None, false)         => format!("{}", entry),
+		}
+	}
+}
+
+/*
+	Builds a matrix incrementally, one row at a time. The first row
+	pushed fixes the number of columns.
+*/
+struct MatrixBuilder
+{
+	m: u32,
+	n: Option<u32>,
+	entries: Vec<f64>,
+}
+
+impl MatrixBuilder
+{
+	pub fn new() -> MatrixBuilder
+	{
+		MatrixBuilder
+		{
+			m: 0,
+			n: None,
+			entries: Vec::new(),
+		}
+	}
+
+	pub fn push_row(&mut self, row: Vec<f64>) -> Result<(), MatrixError>
+	{
+		let n = *self.n.get_or_insert(row.len() as u32);
+
+		if row.len() != n as usize
+		{
+			return Err(MatrixError::RaggedRow { row: self.m as usize, expected: n as usize, found: row.len() });
+		}
+
+		self.entries.extend(row);
+		self.m += 1;
+
+		Ok(())
+	}
+
+	pub fn build(self) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n.unwrap_or(0),
+			entries: self.entries,
+			det_cache: OnceCell::new(),
+		}
+	}
+}
+
+/*
+	Computes the weighted sum of matrices, sum_k weights[k] * matrices[k].
+	All matrices must share the same shape. The function is public while
+	Matrix is not: a public Matrix would hide its unused methods from the
+	dead-code lint.
+*/
+#[allow(private_interfaces)]
+pub fn linear_combination(matrices: &[&Matrix], weights: &[f64]) -> Result<Matrix, MatrixError>
+{
+	if matrices.len() != weights.len()
+	{
+		return Err(MatrixError::InvalidLength { expected: matrices.len(), found: weights.len() });
+	}
+
+	let first   = matrices.first().ok_or(MatrixError::EmptyInput)?;
+	let mut res = Matrix::new(first.m, first.n);
+
+	for (mat, weight) in matrices.iter().zip(weights.iter())
+	{
+		if (mat.m, mat.n) != (res.m, res.n)
+		{
+			return Err(MatrixError::DimensionMismatch { expected: (res.m, res.n), found: (mat.m, mat.n) });
+		}
+
+		for (l, r) in res.entries.iter_mut().zip(mat.entries.iter())
+		{
+			*l += weight * r;
+		}
+	}
+
+	Ok(res)
+}
+
+/*
+	Maps a determinant to -1, 0 or 1, treating it as zero when its magnitude
+	is within errbound, the largest rounding error its computation can carry.
+*/
+fn det_sign_with_bound(det: f64, errbound: f64) -> i32
+{
+	if det > errbound
+	{
+		1
+	}
+	else if det < -errbound
+	{
+		-1
+	}
+	else
+	{
+		0
+	}
+}
+
+/*
+	Orientation test for three points in the plane: 1 if a, b, c turn
+	counterclockwise, -1 if clockwise and 0 if they are (nearly) collinear.
+	This is the sign of the 2x2 determinant of b - a and c - a. As in
+	Shewchuk's predicates the tolerance is relative: a sign is only reported
+	when the determinant exceeds the worst-case rounding error of its two
+	products, so the test behaves the same at every scale.
+*/
+pub fn orient2d(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> i32
+{
+	let eps      = f64::EPSILON / 2f64;
+	let detleft  = (b.0 - a.0) * (c.1 - a.1);
+	let detright = (b.1 - a.1) * (c.0 - a.0);
+	let errbound = (3f64 + 16f64 * eps) * eps * (detleft.abs() + detright.abs());
+
+	det_sign_with_bound(detleft - detright, errbound)
+}
+
+/*
+	Orientation test for four points in space: the sign of the 3x3
+	determinant of b - a, c - a and d - a. It is 1 if d lies on the side of
+	the plane through a, b, c that (b - a) x (c - a) points to, -1 on the
+	other side and 0 if the four points are (nearly) coplanar. The error
+	bound scales with the permanent of the terms, as in orient2d.
+*/
+pub fn orient3d(a: (f64, f64, f64), b: (f64, f64, f64), c: (f64, f64, f64), d: (f64, f64, f64)) -> i32
+{
+	let eps          = f64::EPSILON / 2f64;
+	let (ux, uy, uz) = (b.0 - a.0, b.1 - a.1, b.2 - a.2);
+	let (vx, vy, vz) = (c.0 - a.0, c.1 - a.1, c.2 - a.2);
+	let (wx, wy, wz) = (d.0 - a.0, d.1 - a.1, d.2 - a.2);
+
+	let det = ux * (vy * wz - vz * wy)
+			- uy * (vx * wz - vz * wx)
+			+ uz * (vx * wy - vy * wx);
+
+	let permanent = ux.abs() * ((vy * wz).abs() + (vz * wy).abs())
+			+ uy.abs() * ((vx * wz).abs() + (vz * wx).abs())
+			+ uz.abs() * ((vx * wy).abs() + (vy * wx).abs());
+
+	det_sign_with_bound(det, (7f64 + 56f64 * eps) * eps * permanent)
+}
+
+/*
+	Extracts the raw value stored under key in a .npy header dictionary,
+	e.g. "'<f8'" for descr or "(2, 3)" for shape.
+*/
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Option<&'a str>
+{
+	let pattern = format!("'{}':", key);
+	let start   = header.find(&pattern)? + pattern.len();
+	let rest    = header[start..].trim_start();
+
+	let end = if rest.starts_with('(')
+	{
+		rest.find(')')? + 1
+	}
+	else
+	{
+		rest.find([',', '}'])?
+	};
+
+	Some(rest[..end].trim())
+}
+
+/*
+	Computes the dot product of two vectors of equal length.
+*/
+fn dot(l: &[f64], r: &[f64]) -> f64
+{
+	l.iter()
+	 .zip(r.iter())
+	 .map(|(l, r)| l * r)
+	 .fold(0.0f64, |sum, r| sum + r)
+}
+
+/*
+	Computes the dot product using Neumaier's variant of Kahan summation.
+	The rounding error of every product (recovered exactly with a fused
+	multiply-add) and of every addition is collected in a separate
+	compensation term, which is added back at the end.
+*/
+fn compensated_dot(l: &[f64], r: &[f64]) -> f64
+{
+	let (mut sum, mut comp) = (0f64, 0f64);
+
+	for (a, b) in l.iter().zip(r.iter())
+	{
+		let term = a * b;
+		let next = sum + term;
+
+		comp += a.mul_add(*b, -term);
+
+		if sum.abs() >= term.abs()
+		{
+			comp += (sum - next) + term;
+		}
+		else
+		{
+			comp += (term - next) + sum;
+		}
+
+		sum = next;
+	}
+
+	sum + comp
+}
+
+/*
+	Computes the Euclidean norm of a vector.
+*/
+fn norm(v: &[f64]) -> f64
+{
+	dot(v, v).sqrt()
+}
+
+/*
+	Computes the Euclidean distance between two vectors of equal length.
+*/
+fn distance(l: &[f64], r: &[f64]) -> f64
+{
+	l.iter()
+		.zip(r.iter())
+		.map(|(a, b)| (a - b).powi(2))
+		.sum::<f64>()
+		.sqrt()
+}
+
+impl Clone for Matrix
+{
+	/*
+		A clone starts without a cached determinant, as internal routines
+		freely write to the entries of the working copies they clone.
+	*/
+	fn clone(&self) -> Matrix
+	{
+		Matrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries.clone(),
+			det_cache: OnceCell::new(),
+		}
+	}
+}
+
+impl fmt::Display for Matrix
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		/* 
+			We begin at i = 1 such that the first entry
+		    is not printed on a separate line. (Since 0 % k = 0 for all k.)
+			An empty matrix has no entries, so nothing is printed and
+			the modulus below is never evaluated with n = 0.
+		*/
+		for i in 1..=(self.m * self.n)
+		{
+			let entry = self.entries[(i-1) as usize];
+
+			/* 
+				We will produce a newline if we are on the n-th (n = width) 
+				element within a row. We use a simple modulus check to determine this.
+			*/
+			if i % self.n == 0
+			{
+				writeln!(f, "{}", entry)?;
+			}
+			else
+			{
+				write!(f, "{} ", entry)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl fmt::Display for MatrixError
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			MatrixError::InvalidLength { expected, found } =>
+				write!(f, "expected {} entries, found {}", expected, found),
+			MatrixError::NotSquare { m, n } =>
+				write!(f, "expected a square matrix, found a {}x{} matrix", m, n),
+			MatrixError::DimensionMismatch { expected, found } =>
+				write!(f, "expected a {}x{} matrix, found a {}x{} matrix", expected.0, expected.1, found.0, found.1),
+			MatrixError::Singular =>
+				write!(f, "matrix is singular"),
+			MatrixError::RankDeficient =>
+				write!(f, "matrix does not have full rank"),
+			MatrixError::ZeroTrace =>
+				write!(f, "matrix has a zero trace"),
+			MatrixError::NegativeDeterminant =>
+				write!(f, "matrix has a negative determinant"),
+			MatrixError::DidNotConverge { iterations } =>
+				write!(f, "did not converge within {} iterations", iterations),
+			MatrixError::RaggedRow { row, expected, found } =>
+				write!(f, "row {} has {} entries, expected {}", row, found, expected),
+			MatrixError::EmptyInput =>
+				write!(f, "input is empty"),
+			MatrixError::ZeroVector =>
+				write!(f, "vector has zero length"),
+			MatrixError::TooLargeForCofactor { m } =>
+				write!(f, "{}x{} matrix is too large for a cofactor expansion", m, m),
+			MatrixError::Parse { line, token } =>
+				write!(f, "cannot parse \"{}\" on line {} as a number", token, line),
+			MatrixError::Io(message) =>
+				write!(f, "i/o error: {}", message),
+			MatrixError::NotSymmetric =>
+				write!(f, "matrix is not symmetric"),
+			MatrixError::OutOfBounds { index, bound } =>
+				write!(f, "index {} is out of bounds, expected less than {}", index, bound),
+			MatrixError::NotPositiveDefinite =>
+				write!(f, "matrix is not positive definite"),
+			MatrixError::InvalidNpy(reason) =>
+				write!(f, "invalid .npy data: {}", reason),
+			MatrixError::ZeroDiagonal { index } =>
+				write!(f, "diagonal entry {} is zero", index),
+			MatrixError::DimensionOverflow =>
+				write!(f, "resulting dimensions do not fit in u32"),
+		}
+	}
+}
+
+impl std::error::Error for MatrixError {}
+
+/*
+	A matrix with complex entries, mirroring the real Matrix API.
+	Only available with the "complex" feature enabled.
+*/
+#[cfg(feature = "complex")]
+#[derive(Clone)]
+struct ComplexMatrix
+{
+	m: u32,
+	n: u32,
+	entries: Vec<Complex<f64>>,
+}
+
+#[cfg(feature = "complex")]
+impl ComplexMatrix
+{
+	pub fn new(m: u32, n: u32) -> ComplexMatrix
+	{
+		ComplexMatrix
+		{
+			m,
+			n,
+			entries: vec![Complex::new(0f64, 0f64); m as usize * n as usize],
+		}
+	}
+
+	/*
+		Converts 2D coordinates to a 1D index.
+	*/
+	fn get_index(&self, i: u32, j: u32) -> usize
+	{
+		(i * self.n + j) as usize
+	}
+
+	/*
+		Converts a 1D index to 2D coordinates.
+	*/
+	fn get_coords(&self, idx: usize) -> (u32, u32)
+	{
+		(idx as u32 / self.n, idx as u32 % self.n)
+	}
+
+	/*
+		Produces a submatrix with row i and column j missing.
+	*/
+	pub fn sub_matrix(&self, i: u32, j: u32) -> ComplexMatrix
+	{
+		ComplexMatrix
+		{
+			m: self.m - 1,
+			n: self.n - 1,
+			entries: self.entries
+						 .iter()
+						 .enumerate()
+						 .filter(|&(idx, _)| {
+							let (row, col) = self.get_coords(idx);
+							!(row == i || col == j)
+						 })
+						 .map(|(_, elem)| *elem)
+						 .collect::<Vec<_>>(),
+		}
+	}
+
+	pub fn add(&self, other: &ComplexMatrix) -> ComplexMatrix
+	{
+		if (self.m, self.n) != (other.m, other.n)
+		{
+			panic!("cannot add matrices of differing dimensions");
+		}
+
+		ComplexMatrix
+		{
+			m: self.m,
+			n: self.n,
+			entries: self.entries
+						 .iter()
+						 .zip(other.entries.iter())
+						 .map(|(l, r)| *l + *r)
+						 .collect::<Vec<_>>(),
+		}
+	}
+
+	pub fn mul(&self, other: &ComplexMatrix) -> ComplexMatrix
+	{
+		if self.n != other.m
+		{
+			panic!("cannot multiply matrices with differing inner dimensions");
+		}
+
+		let mut res = ComplexMatrix::new(self.m, other.n);
+
+		for i in 0..self.m
+		{
+			for j in 0..other.n
+			{
+				let idx = res.get_index(i, j);
+
+				res.entries[idx] = (0..self.n)
+					.map(|k| self.entries[self.get_index(i, k)] * other.entries[other.get_index(k, j)])
+					.fold(Complex::new(0f64, 0f64), |sum, r| sum + r);
+			}
+		}
+
+		res
+	}
+
+	pub fn transpose(&self) -> ComplexMatrix
+	{
+		let mut res = ComplexMatrix::new(self.n, self.m);
+
+		for (idx, entry) in self.entries.iter().enumerate()
+		{
+			let (i, j) = self.get_coords(idx);
+			let dst    = res.get_index(j, i);
+			res.entries[dst] = *entry;
+		}
+
+		res
+	}
+
+	/*
+		Returns the conjugate transpose A^H, i.e. the transpose with
+		every entry replaced by its complex conjugate.
+	*/
+	pub fn hermitian_transpose(&self) -> ComplexMatrix
+	{
+		let mut res = self.transpose();
+
+		for entry in res.entries.iter_mut()
+		{
+			*entry = entry.conj();
+		}
+
+		res
+	}
+
+	pub fn det(&self) -> Complex<f64>
+	{
+		if self.m != self.n
+		{
+			panic!("cannot compute determinant for non-square matrix")
+		}
+
+		match self.m
+		{
+			0 => Complex::new(1f64, 0f64),
+			1 => self.entries[0],
+			2 => {
+				let (a, b, c, d) = (
+					self.entries[self.get_index(0, 0)],
+					self.entries[self.get_index(0, 1)],
+					self.entries[self.get_index(1, 0)],
+					self.entries[self.get_index(1, 1)]
+				);
+				a * d - b * c
+			}
+			_ => {
+				/*
+					Laplace expansion along the first row, as for real matrices.
+				*/
+				let mut det = Complex::new(0f64, 0f64);
+
+				for col in 0..self.n
+				{
+					let entry = self.entries[self.get_index(0, col)];
+					let subm  = self.sub_matrix(0, col);
+					let val   = entry * subm.det();
+
+					if col % 2 == 0
+					{
+						det += val;
+					}
+					else
+					{
+						det -= val;
+					}
+				}
+
+				det
+			},
+		}
+	}
+}
+
+#[cfg(feature = "complex")]
+impl fmt::Display for ComplexMatrix
+{
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
 	{
-		/* 
-			We begin at i = 1 such that the first entry
-		    is not printed on a separate line. (Since 0 % k = 0 for all k.)
+		for i in 1..=(self.m * self.n)
+		{
+			let entry = self.entries[(i-1) as usize];
+
+			if i % self.n == 0
+			{
+				writeln!(f, "{}", entry)?;
+			}
+			else
+			{
+				write!(f, "{} ", entry)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+fn main()
+{
+	let mut mat = Matrix::new(3, 3);
+	let mut rng = rand::thread_rng();
+
+	for i in 0..(mat.m * mat.n)
+	{
+		mat.entries[i as usize] = rng.gen_range(1.0f64..20.0f64);
+	}
+
+	let mut mat2 = Matrix::new(3, 3);
+
+	for i in 0..(mat2.m * mat2.n)
+	{
+		mat2.entries[i as usize] = rng.gen_range(1.0f64..20.0f64);
+	}
+
+	println!("{}", mat);
+	println!("{}", mat2);
+	println!("det(mat) = {}", mat.det());
+	println!("sum: {}", mat.add(&mat2));
+	println!("mul: {}", mat.mul(&mat2));
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	fn mat(rows: &[&[f64]]) -> Matrix
+	{
+		let mut res = Matrix::new(rows.len() as u32, rows.first().map_or(0, |row| row.len()) as u32);
+		res.entries = rows.concat();
+		res
+	}
+
+	fn scaled(a: &Matrix, factor: f64) -> Matrix
+	{
+		let mut res = a.clone();
+
+		for entry in res.entries.iter_mut()
+		{
+			*entry *= factor;
+		}
+
+		res
+	}
+
+	fn assert_close(actual: &Matrix, expected: &Matrix, tol: f64)
+	{
+		assert_eq!((actual.m, actual.n), (expected.m, expected.n));
+
+		for (idx, (l, r)) in actual.entries.iter().zip(expected.entries.iter()).enumerate()
+		{
+			assert!((l - r).abs() <= tol, "entries at index {} differ by {}:\n{}\n{}", idx, (l - r).abs(), actual, expected);
+		}
+	}
+
+	#[test]
+	fn skew_symmetric_3d_matches_cross_product()
+	{
+		let (v, w) = ([1f64, -2f64, 3f64], [4f64, 0.5f64, -1f64]);
+		let cross  = [v[1] * w[2] - v[2] * w[1], v[2] * w[0] - v[0] * w[2], v[0] * w[1] - v[1] * w[0]];
+
+		let skew = Matrix::skew_symmetric_3d(&v).unwrap();
+
+		assert_eq!(skew.mul_vec(&w).unwrap(), cross.to_vec());
+		assert_eq!(Matrix::skew_symmetric_3d(&[1f64, 2f64]).err(), Some(MatrixError::InvalidLength { expected: 3, found: 2 }));
+	}
+
+	#[test]
+	fn det_sign_reports_orientation()
+	{
+		assert_eq!(mat(&[&[2f64, 1f64], &[1f64, 3f64]]).det_sign(), Ok(1));
+		assert_eq!(mat(&[&[0f64, 1f64], &[1f64, 0f64]]).det_sign(), Ok(-1));
+		assert_eq!(mat(&[&[1f64, 2f64], &[2f64, 4f64]]).det_sign(), Ok(0));
+	}
+
+	#[test]
+	fn det_sign_flushes_numerical_singularity_at_any_scale()
+	{
+		let a = mat(&[&[0.1, 0.2, 0.3], &[0.4, 0.5, 0.6], &[0.7, 0.8, 0.9]]);
+
+		assert_eq!(a.det_sign(), Ok(0));
+
+		/*
+			Scaling the whole matrix changes the determinant by s^n but must
+			not change its sign, so the pivot test has to be relative.
+		*/
+		assert_eq!(scaled(&a, 1e6).det_sign(), Ok(0));
+		assert_eq!(scaled(&a, 1e-6).det_sign(), Ok(0));
+		assert_eq!(mat(&[&[1e-13, 0f64], &[0f64, 1e-13]]).det_sign(), Ok(1));
+		assert_eq!(mat(&[&[0f64, 1e-20], &[1e-20, 0f64]]).det_sign(), Ok(-1));
+		assert_eq!(Matrix::new(2, 2).det_sign(), Ok(0));
+	}
+
+	#[test]
+	fn parts_round_trip()
+	{
+		let a               = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+		let (m, n, entries) = a.into_parts();
+		let b               = Matrix::from_parts(m, n, entries).unwrap();
+
+		assert_eq!((m, n), (2, 3));
+		assert_eq!(b.entries, vec![1f64, 2f64, 3f64, 4f64, 5f64, 6f64]);
+		assert_eq!(Matrix::from_parts(2, 2, vec![1f64]).err(), Some(MatrixError::InvalidLength { expected: 4, found: 1 }));
+	}
+
+	#[test]
+	fn from_parts_rejects_lengths_overflowing_u32()
+	{
+		assert!(matches!(Matrix::from_parts(65536, 65536, Vec::new()), Err(MatrixError::InvalidLength { found: 0, .. })));
+	}
+
+	#[cfg(feature = "complex")]
+	fn cmat(m: u32, n: u32, entries: &[(f64, f64)]) -> ComplexMatrix
+	{
+		ComplexMatrix
+		{
+			m,
+			n,
+			entries: entries.iter().map(|&(re, im)| Complex::new(re, im)).collect::<Vec<_>>(),
+		}
+	}
+
+	#[cfg(feature = "complex")]
+	#[test]
+	fn complex_mul_and_det()
+	{
+		let a = cmat(2, 2, &[(1f64, 1f64), (2f64, 0f64), (0f64, 0f64), (0f64, 1f64)]);
+		let b = cmat(2, 2, &[(1f64, 0f64), (0f64, -1f64), (1f64, 0f64), (1f64, 0f64)]);
+
+		assert_eq!(a.mul(&b).entries, cmat(2, 2, &[(3f64, 1f64), (3f64, -1f64), (0f64, 1f64), (0f64, 1f64)]).entries);
+		assert_eq!(a.det(), Complex::new(-1f64, 1f64));
+	}
+
+	#[cfg(feature = "complex")]
+	#[test]
+	fn complex_add_is_entrywise()
+	{
+		let a = cmat(1, 2, &[(1f64, 2f64), (-3f64, 0.5)]);
+		let b = cmat(1, 2, &[(0.5, -2f64), (3f64, 1f64)]);
+
+		assert_eq!(a.add(&b).entries, cmat(1, 2, &[(1.5, 0f64), (0f64, 1.5)]).entries);
+	}
+
+	#[cfg(feature = "complex")]
+	#[test]
+	fn hermitian_transpose_conjugates_and_transposes()
+	{
+		let a  = cmat(2, 3, &[(1f64, 1f64), (2f64, 0f64), (0f64, -3f64), (4f64, 2f64), (0f64, 1f64), (5f64, 0f64)]);
+		let ah = a.hermitian_transpose();
+
+		assert_eq!((ah.m, ah.n), (3, 2));
+		assert_eq!(ah.entries, cmat(3, 2, &[(1f64, -1f64), (4f64, -2f64), (2f64, 0f64), (0f64, -1f64), (0f64, 3f64), (5f64, 0f64)]).entries);
+		assert_eq!(ah.hermitian_transpose().entries, a.entries);
+	}
+
+	#[test]
+	fn is_diagonal_and_diagonal_matrix()
+	{
+		let d     = mat(&[&[2f64, 0f64], &[1e-14, -3f64]]);
+		let dense = mat(&[&[1f64, 2f64], &[3f64, 4f64]]);
+
+		assert!(d.is_diagonal(1e-12));
+		assert!(!dense.is_diagonal(1e-12));
+		assert!(!Matrix::new(2, 3).is_diagonal(1e-12));
+		assert_eq!(dense.diagonal_matrix().entries, vec![1f64, 0f64, 0f64, 4f64]);
+	}
+
+	#[test]
+	fn residual_distinguishes_exact_and_wrong_solutions()
+	{
+		let a = mat(&[&[2f64, 1f64], &[1f64, 3f64]]);
+		let b = [3f64, 5f64];
+
+		assert!(a.residual(&[0.8, 1.4], &b).unwrap() < 1e-12);
+		assert!(a.residual(&[10f64, -10f64], &b).unwrap() > 1f64);
+		assert_eq!(a.residual(&[1f64, 1f64], &[1f64]).err(), Some(MatrixError::InvalidLength { expected: 2, found: 1 }));
+	}
+
+	#[test]
+	fn pinv_satisfies_the_penrose_identity()
+	{
+		let tall = mat(&[&[1f64, 2f64], &[3f64, 4f64], &[5f64, 7f64]]);
+		let wide = tall.transpose();
+
+		assert_close(&tall.mul(&tall.pinv().unwrap()).mul(&tall), &tall, 1e-10);
+		assert_close(&wide.mul(&wide.pinv().unwrap()).mul(&wide), &wide, 1e-10);
+		assert_eq!(mat(&[&[1f64, 2f64], &[2f64, 4f64], &[3f64, 6f64]]).pinv().err(), Some(MatrixError::RankDeficient));
+	}
+
+	#[test]
+	fn pinv_rank_test_is_relative_to_scale()
+	{
+		let small = scaled(&Matrix::identity(2), 1e-7);
+
+		assert_close(&small.pinv().unwrap(), &scaled(&Matrix::identity(2), 1e7), 1e-3);
+		assert_eq!(Matrix::new(2, 2).pinv().err(), Some(MatrixError::RankDeficient));
+		assert_eq!(Matrix::new(3, 0).pinv().unwrap().m, 0);
+	}
+
+	#[test]
+	fn inverse_is_independent_of_scale()
+	{
+		let a = mat(&[&[4f64, 7f64], &[2f64, 6f64]]);
+
+		for scale in [1e-13, 1f64, 1e13]
+		{
+			let b = scaled(&a, scale);
+			assert_close(&b.mul(&b.inverse().unwrap()), &Matrix::identity(2), 1e-12);
+		}
+
+		assert_eq!(mat(&[&[1e8, 2e8], &[2e8, 4e8]]).inverse().err(), Some(MatrixError::Singular));
+		assert_eq!(mat(&[&[0.1, 0.2, 0.3], &[0.4, 0.5, 0.6], &[0.7, 0.8, 0.9]]).inverse().err(), Some(MatrixError::Singular));
+	}
+
+	#[test]
+	fn covariance_matches_hand_computation()
+	{
+		let data = mat(&[&[2f64, 1f64], &[4f64, 3f64], &[6f64, 8f64]]);
+
+		assert_close(&data.covariance(), &mat(&[&[4f64, 7f64], &[7f64, 13f64]]), 1e-12);
+		assert_close(&mat(&[&[1f64, 2f64]]).covariance(), &Matrix::new(0, 0), 0f64);
+	}
+
+	#[test]
+	fn triangular_parts_zero_the_other_side()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64], &[7f64, 8f64, 9f64]]);
+
+		assert_close(&a.upper_triangular(), &mat(&[&[1f64, 2f64, 3f64], &[0f64, 5f64, 6f64], &[0f64, 0f64, 9f64]]), 0f64);
+		assert_close(&a.lower_triangular(), &mat(&[&[1f64, 0f64, 0f64], &[4f64, 5f64, 0f64], &[7f64, 8f64, 9f64]]), 0f64);
+	}
+
+	#[test]
+	fn map_indexed_passes_coordinates()
+	{
+		let a = Matrix::new(2, 3).map_indexed(|i, j, _| (i * 10 + j) as f64);
+
+		assert_close(&a, &mat(&[&[0f64, 1f64, 2f64], &[10f64, 11f64, 12f64]]), 0f64);
+		assert_close(&a.map(|entry| -entry), &mat(&[&[0f64, -1f64, -2f64], &[-10f64, -11f64, -12f64]]), 0f64);
+	}
+
+	#[test]
+	fn power_iteration_finds_the_dominant_pair()
+	{
+		let a           = mat(&[&[4f64, 1f64], &[2f64, 3f64]]);
+		let (lambda, v) = a.power_iteration(1000, 1e-10).unwrap();
+		let av          = a.mul_vec(&v).unwrap();
+
+		assert!((lambda - 5f64).abs() < 1e-8);
+		assert!(av.iter().zip(v.iter()).all(|(l, r)| (l - lambda * r).abs() < 1e-8));
+		assert_eq!(Matrix::new(2, 3).power_iteration(10, 1e-10).err(), Some(MatrixError::NotSquare { m: 2, n: 3 }));
+	}
+
+	#[test]
+	fn power_iteration_escapes_a_non_dominant_all_ones_eigenvector()
+	{
+		let laplacian   = mat(&[&[1f64, -1f64, 0f64], &[-1f64, 2f64, -1f64], &[0f64, -1f64, 1f64]]);
+		let (lambda, _) = laplacian.power_iteration(1000, 1e-10).unwrap();
+
+		assert!((lambda - 3f64).abs() < 1e-8);
+	}
+
+	#[test]
+	fn power_iteration_is_reproducible()
+	{
+		let a = mat(&[&[2f64, 1f64, 0f64], &[1f64, 3f64, 1f64], &[0f64, 1f64, 4f64]]);
+
+		assert_eq!(a.power_iteration(1000, 1e-10), a.power_iteration(1000, 1e-10));
+	}
+
+	#[test]
+	fn banded_builds_a_tridiagonal_pattern()
+	{
+		let expected = mat(&[
+			&[1f64, 1f64, 0f64, 0f64],
+			&[1f64, 1f64, 1f64, 0f64],
+			&[0f64, 1f64, 1f64, 1f64],
+			&[0f64, 0f64, 1f64, 1f64],
+		]);
+		let lower    = mat(&[&[1f64, 0f64, 0f64], &[1f64, 1f64, 0f64], &[1f64, 1f64, 1f64]]);
+
+		assert_close(&Matrix::banded(4, 1, 1, 1f64), &expected, 0f64);
+		assert_close(&Matrix::banded(3, u32::MAX, 0, 1f64), &lower, 0f64);
+	}
+
+	#[test]
+	fn masks_compare_against_the_threshold()
+	{
+		let a = mat(&[&[-1f64, 0.5, 1f64], &[1.5, 2f64, 0.99]]);
+
+		assert_close(&a.gt_mask(1f64), &mat(&[&[0f64, 0f64, 0f64], &[1f64, 1f64, 0f64]]), 0f64);
+		assert_close(&a.lt_mask(1f64), &mat(&[&[1f64, 1f64, 0f64], &[0f64, 0f64, 1f64]]), 0f64);
+	}
+
+	#[test]
+	fn symmetric_and_antisymmetric_parts_reconstruct()
+	{
+		let a    = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64], &[7f64, 8f64, 10f64]]);
+		let sym  = a.symmetric_part().unwrap();
+		let anti = a.antisymmetric_part().unwrap();
+
+		assert_close(&sym.add(&anti), &a, 1e-12);
+		assert_close(&sym, &sym.transpose(), 0f64);
+		assert_close(&anti, &anti.transpose().map(|entry| -entry), 0f64);
+		assert_eq!(Matrix::new(2, 3).symmetric_part().err(), Some(MatrixError::NotSquare { m: 2, n: 3 }));
+	}
+
+	#[test]
+	fn in_place_updates_match_allocating_ones()
+	{
+		let x = mat(&[&[1f64, 2f64], &[3f64, 4f64]]);
+		let d = mat(&[&[0.5, -1f64], &[2f64, 0f64]]);
+
+		let mut scaled = x.clone();
+		scaled.scale_assign(2.5);
+		assert_close(&scaled, &x.map(|entry| entry * 2.5), 0f64);
+
+		let mut sum = x.clone();
+		sum.add_assign(&d).unwrap();
+		assert_close(&sum, &x.add(&d), 0f64);
+
+		assert!(sum.add_assign(&Matrix::new(1, 2)).is_err());
+	}
+
+	#[test]
+	fn shape_info_covers_every_category()
+	{
+		assert_eq!(Matrix::new(0, 3).shape_info(), ShapeInfo::Empty);
+		assert_eq!(Matrix::new(1, 1).shape_info(), ShapeInfo::Scalar);
+		assert_eq!(Matrix::new(1, 4).shape_info(), ShapeInfo::RowVector);
+		assert_eq!(Matrix::new(4, 1).shape_info(), ShapeInfo::ColumnVector);
+		assert_eq!(Matrix::new(3, 3).shape_info(), ShapeInfo::Square);
+		assert_eq!(Matrix::new(4, 2).shape_info(), ShapeInfo::Tall);
+		assert_eq!(Matrix::new(2, 4).shape_info(), ShapeInfo::Wide);
+	}
+
+	fn random(m: u32, n: u32) -> Matrix
+	{
+		let mut rng = rand::thread_rng();
+		let entries = (0..m * n).map(|_| rng.gen_range(-10f64..10f64)).collect::<Vec<_>>();
+
+		Matrix::from_parts(m, n, entries).unwrap()
+	}
+
+	fn assert_svd(a: &Matrix)
+	{
+		let (u, s, v) = a.svd().unwrap();
+		let k         = s.len() as u32;
+		let sigma     = Matrix::identity(k).map_indexed(|i, _, entry| entry * s[i as usize]);
+
+		assert_eq!(k, a.m.min(a.n));
+		assert!(s.iter().all(|&sigma| sigma >= 0f64));
+		assert!(s.windows(2).all(|pair| pair[0] >= pair[1]));
+		assert_close(&u.mul(&sigma).mul(&v.transpose()), a, 1e-10);
+		assert_close(&u.transpose().mul(&u), &Matrix::identity(k), 1e-10);
+		assert_close(&v.transpose().mul(&v), &Matrix::identity(k), 1e-10);
+	}
+
+	#[test]
+	fn svd_reconstructs_random_matrices()
+	{
+		assert_svd(&random(4, 3));
+		assert_svd(&random(3, 4));
+	}
+
+	#[test]
+	fn svd_keeps_u_orthonormal_for_rank_deficient_input()
+	{
+		let a = mat(&[&[1f64, 2f64], &[2f64, 4f64], &[3f64, 6f64]]);
+
+		assert_svd(&a);
+		assert_svd(&a.transpose());
+		assert_svd(&Matrix::new(3, 2));
+		assert!(a.svd().unwrap().1[1].abs() < 1e-12);
+	}
+
+	#[test]
+	fn col_major_round_trip()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+
+		assert_eq!(a.to_col_major(), vec![1f64, 4f64, 2f64, 5f64, 3f64, 6f64]);
+		assert_close(&Matrix::from_col_major(2, 3, &a.to_col_major()).unwrap(), &a, 0f64);
+		assert!(matches!(Matrix::from_col_major(65536, 65536, &[]), Err(MatrixError::InvalidLength { found: 0, .. })));
+	}
+
+	#[test]
+	fn chop_zeroes_entries_below_the_tolerance()
+	{
+		let a = mat(&[&[9e-11, -9e-11], &[1.1e-10, 3f64]]);
+
+		assert_close(&a.chop(1e-10), &mat(&[&[0f64, 0f64], &[1.1e-10, 3f64]]), 0f64);
+	}
+
+	#[test]
+	fn normalized_trace_and_determinant_are_one()
+	{
+		let a = mat(&[&[2f64, 1f64], &[1f64, 3f64]]);
+
+		assert!((a.normalize_trace().unwrap().trace().unwrap() - 1f64).abs() < 1e-12);
+		assert!((a.normalize_det().unwrap().det() - 1f64).abs() < 1e-12);
+		assert_eq!(mat(&[&[1f64, 0f64], &[0f64, -1f64]]).normalize_trace().err(), Some(MatrixError::ZeroTrace));
+		assert_eq!(mat(&[&[1f64, 2f64], &[2f64, 4f64]]).normalize_det().err(), Some(MatrixError::Singular));
+	}
+
+	#[test]
+	fn normalization_is_independent_of_scale()
+	{
+		let covariance = mat(&[&[2e-5, 1e-5, 0f64], &[1e-5, 3e-5, 0f64], &[0f64, 0f64, 1e-5]]);
+		let small      = Matrix::identity(4).map(|entry| entry * 1e-4);
+		let tiny       = Matrix::identity(2).map(|entry| entry * 1e-13);
+
+		assert!((covariance.normalize_det().unwrap().det() - 1f64).abs() < 1e-12);
+		assert_close(&small.normalize_det().unwrap(), &Matrix::identity(4), 1e-12);
+		assert_close(&tiny.normalize_trace().unwrap(), &Matrix::identity(2).map(|entry| entry * 0.5), 1e-12);
+		assert_eq!(mat(&[&[1e-20, 0f64], &[0f64, -1e-20]]).normalize_trace().err(), Some(MatrixError::ZeroTrace));
+		assert_eq!(mat(&[&[1e8, 2e8], &[2e8, 4e8]]).normalize_det().err(), Some(MatrixError::Singular));
+	}
+
+	#[test]
+	fn builder_collects_rows_and_rejects_ragged_ones()
+	{
+		let mut builder = MatrixBuilder::new();
+		builder.push_row(vec![1f64, 2f64]).unwrap();
+		builder.push_row(vec![3f64, 4f64]).unwrap();
+
+		assert_eq!(builder.push_row(vec![5f64]), Err(MatrixError::RaggedRow { row: 2, expected: 2, found: 1 }));
+		assert_close(&builder.build(), &mat(&[&[1f64, 2f64], &[3f64, 4f64]]), 0f64);
+	}
+
+	#[test]
+	fn nested_round_trip()
+	{
+		let rows = vec![vec![1f64, 2f64, 3f64], vec![4f64, 5f64, 6f64]];
+
+		assert_eq!(Matrix::from_rows(rows.clone()).unwrap().to_nested(), rows);
+	}
+
+	#[test]
+	fn linear_combination_weights_each_matrix()
+	{
+		let (a, b, c) = (Matrix::identity(2), mat(&[&[1f64, 1f64], &[1f64, 1f64]]), mat(&[&[1f64, 2f64], &[3f64, 4f64]]));
+		let res       = linear_combination(&[&a, &b, &c], &[2f64, -1f64, 0.5]).unwrap();
+
+		assert_close(&res, &mat(&[&[1.5, 0f64], &[0.5, 3f64]]), 1e-12);
+		assert_eq!(linear_combination(&[&a, &b], &[1f64]).err(), Some(MatrixError::InvalidLength { expected: 2, found: 1 }));
+		assert!(matches!(linear_combination(&[&a, &Matrix::new(2, 3)], &[1f64, 1f64]), Err(MatrixError::DimensionMismatch { .. })));
+	}
+
+	#[test]
+	fn is_invertible_guards_solves()
+	{
+		assert!(mat(&[&[2f64, 1f64], &[1f64, 3f64]]).is_invertible(DEFAULT_TOL));
+		assert!(!mat(&[&[1f64, 2f64], &[2f64, 4f64]]).is_invertible(DEFAULT_TOL));
+		assert!(!Matrix::new(2, 3).is_invertible(DEFAULT_TOL));
+	}
+
+	#[test]
+	fn sarrus_matches_the_recursive_expansion()
+	{
+		for _ in 0..20
+		{
+			let a         = random(3, 3);
+			let recursive = (0..3)
+				.map(|col| {
+					let sign = if col % 2 == 0 { 1f64 } else { -1f64 };
+					sign * a.entries[a.get_index(0, col)] * a.sub_matrix(0, col).det()
+				})
+				.sum::<f64>();
+
+			assert!((a.det() - recursive).abs() <= 1e-9 * recursive.abs().max(1f64));
+		}
+	}
+
+	#[test]
+	fn format_with_honours_the_config()
+	{
+		let a = mat(&[&[1.5, -2f64], &[1500f64, 0.25]]);
+
+		let fixed      = DisplayConfig::new().precision(2).separator(", ");
+		let scientific = DisplayConfig::new().scientific(true).precision(1);
+
+		assert_eq!(a.format_with(&fixed), "1.50, -2.00\n1500.00, 0.25\n");
+		assert_eq!(a.format_with(&scientific), "1.5e0 -2.0e0\n1.5e3 2.5e-1\n");
+	}
+
+	#[test]
+	fn sum_axis_collapses_rows_or_columns()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+
+		assert_eq!(a.sum_axis(Axis::Rows).to_nested(), vec![vec![5f64, 7f64, 9f64]]);
+		assert_eq!(a.sum_axis(Axis::Cols).to_nested(), vec![vec![6f64], vec![15f64]]);
+	}
+
+	#[test]
+	fn argmax_and_argmin_locate_extremes()
+	{
+		let a = mat(&[&[1f64, 7f64, 3f64], &[-4f64, 5f64, 6f64]]);
+
+		assert_eq!(a.argmax(), Some((0, 1)));
+		assert_eq!(a.argmin(), Some((1, 0)));
+		assert_eq!(Matrix::new(0, 0).argmax(), None);
+		assert_eq!(Matrix::new(0, 0).argmin(), None);
+	}
+
+	#[test]
+	fn zero_and_identity_checks_respect_the_tolerance()
+	{
+		let near_identity = Matrix::identity(3).map_indexed(|i, j, entry| if i == 0 && j == 2 { 1e-13 } else { entry });
+
+		assert!(Matrix::new(2, 2).map(|_| 1e-13).is_zero(1e-12));
+		assert!(!Matrix::new(2, 2).map(|_| 1e-3).is_zero(1e-12));
+		assert!(near_identity.is_identity(1e-12));
+		assert!(!near_identity.is_identity(1e-14));
+		assert!(!Matrix::new(2, 3).is_identity(1e-12));
+	}
+
+	#[test]
+	fn iter_nonzero_yields_meaningful_entries()
+	{
+		let a = mat(&[&[0f64, 2f64, 0f64], &[1e-15, 0f64, -3f64]]);
+
+		assert_eq!(a.iter_nonzero(1e-12).collect::<Vec<_>>(), vec![(0, 1, 2f64), (1, 2, -3f64)]);
+	}
+
+	#[test]
+	fn rotation_about_z_embeds_the_planar_rotation()
+	{
+		let angle      = std::f64::consts::FRAC_PI_2;
+		let (sin, cos) = angle.sin_cos();
+		let planar     = mat(&[&[cos, -sin, 0f64], &[sin, cos, 0f64], &[0f64, 0f64, 1f64]]);
+		let r          = Matrix::rotation_axis_angle(&[0f64, 0f64, 2f64], angle).unwrap();
+
+		assert_close(&r, &planar, 1e-12);
+
+		let rotated = r.mul_vec(&[1f64, 0f64, 0f64]).unwrap();
+		assert!((rotated[0]).abs() < 1e-12 && (rotated[1] - 1f64).abs() < 1e-12 && rotated[2].abs() < 1e-12);
+		assert_eq!(Matrix::rotation_axis_angle(&[0f64, 0f64, 0f64], angle).err(), Some(MatrixError::ZeroVector));
+	}
+
+	#[test]
+	fn to_latex_renders_a_bmatrix()
+	{
+		let a = mat(&[&[1f64, 2.5], &[-3f64, 4f64]]);
+
+		assert_eq!(a.to_latex(), "\\begin{bmatrix}\n1 & 2.5 \\\\\n-3 & 4\n\\end{bmatrix}");
+	}
+
+	#[test]
+	fn solve_many_matches_individual_solves()
+	{
+		let a = mat(&[&[4f64, 1f64, 0f64], &[1f64, 3f64, 1f64], &[0f64, 1f64, 2f64]]);
+		let b = mat(&[&[1f64, 0f64], &[2f64, 1f64], &[3f64, -1f64]]);
+		let x = a.solve_many(&b).unwrap();
+
+		for j in 0..2
+		{
+			let single = a.solve(&b.get_col_vec(j)).unwrap();
+			assert!(x.get_col_vec(j).iter().zip(single.iter()).all(|(l, r)| (l - r).abs() < 1e-12));
+		}
+
+		assert_eq!((x.m, x.n), (b.m, b.n));
+
+		let tiny = a.map(|entry| entry * 1e-13);
+		assert_close(&tiny.solve_many(&b).unwrap(), &x.map(|entry| entry * 1e13), 1e-12 * 1e13);
+	}
+
+	#[test]
+	fn degenerate_shapes_behave_sensibly()
+	{
+		let empty = Matrix::new(0, 0);
+
+		assert_eq!(mat(&[&[-7f64]]).det(), -7f64);
+		assert_eq!(empty.det(), 1f64);
+		assert_close(&empty.add(&empty), &Matrix::new(0, 0), 0f64);
+		assert_close(&empty.mul(&empty), &Matrix::new(0, 0), 0f64);
+		assert_eq!(Matrix::new(2, 0).mul(&Matrix::new(0, 3)).to_nested(), vec![vec![0f64; 3]; 2]);
+		assert_eq!(empty.to_string(), "");
+		assert_eq!(Matrix::new(0, 3).to_string(), "");
+	}
+
+	#[test]
+	fn reshape_order_distinguishes_layouts()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+
+		assert_eq!(a.reshape_order(3, 2, Order::RowMajor).unwrap().to_nested(), vec![vec![1f64, 2f64], vec![3f64, 4f64], vec![5f64, 6f64]]);
+		assert_eq!(a.reshape_order(3, 2, Order::ColMajor).unwrap().to_nested(), vec![vec![1f64, 5f64], vec![4f64, 3f64], vec![2f64, 6f64]]);
+		assert!(a.reshape_order(4, 2, Order::RowMajor).is_err());
+	}
+
+	#[test]
+	fn try_det_refuses_large_cofactor_expansions()
+	{
+		let a = mat(&[&[2f64, 0f64, 1f64], &[1f64, 3f64, 2f64], &[1f64, 1f64, 2f64]]);
+
+		assert_eq!(a.try_det(), Ok(6f64));
+		assert_eq!(a.try_det_with_limit(2), Err(MatrixError::TooLargeForCofactor { m: 3 }));
+		assert_eq!(Matrix::identity(COFACTOR_LIMIT + 1).try_det(), Err(MatrixError::TooLargeForCofactor { m: COFACTOR_LIMIT + 1 }));
+	}
+
+	#[test]
+	fn gram_matches_explicit_products()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+
+		assert_close(&a.gram(), &a.transpose().mul(&a), 1e-12);
+		assert_close(&a.gram_outer(), &a.mul(&a.transpose()), 1e-12);
+	}
+
+	#[test]
+	fn split_lu_recovers_packed_factors()
+	{
+		let l      = mat(&[&[1f64, 0f64, 0f64], &[2f64, 1f64, 0f64], &[-1f64, 0.5, 1f64]]);
+		let u      = mat(&[&[4f64, 3f64, 1f64], &[0f64, -2f64, 5f64], &[0f64, 0f64, 7f64]]);
+		let packed = l.add(&u).add(&Matrix::identity(3).map(|entry| -entry));
+
+		let (l2, u2) = Matrix::split_lu(&packed);
+
+		assert_eq!(l2.to_nested(), l.to_nested());
+		assert_eq!(u2.to_nested(), u.to_nested());
+	}
+
+	#[test]
+	fn rank_svd_is_robust_to_perturbation()
+	{
+		let clean     = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64], &[7f64, 8f64, 9f64]]);
+		let perturbed = mat(&[&[1f64, 2f64], &[2f64, 4f64 + 1e-10]]);
+
+		assert_eq!(clean.rank_svd(1e-10), Ok(2));
+		assert_eq!(clean.pivot_columns(1e-10).len(), 2);
+
+		assert_eq!(perturbed.rank_svd(1e-8), Ok(1));
+		assert_eq!(perturbed.pivot_columns(DEFAULT_TOL).len(), 2);
+	}
+
+	#[test]
+	fn mul_accurate_recovers_cancelled_terms()
+	{
+		let row = mat(&[&[1e16, 1f64, -1e16]]);
+		let col = Matrix::new(3, 1).map(|_| 1f64);
+
+		assert_eq!(row.mul(&col).entries[0], 0f64);
+		assert_eq!(row.mul_accurate(&col).entries[0], 1f64);
+
+		let (a, b) = (random(4, 5), random(5, 3));
+		assert_close(&a.mul_accurate(&b), &a.mul(&b), 1e-10);
+	}
+
+	#[test]
+	fn from_reader_streams_a_grid()
+	{
+		let input = "1 2 3\n\n4.5  -5 6e1\n".as_bytes();
+
+		assert_eq!(Matrix::from_reader(input).unwrap().to_nested(), vec![vec![1f64, 2f64, 3f64], vec![4.5, -5f64, 60f64]]);
+		assert_eq!(Matrix::from_reader("1 2\n3\n".as_bytes()).err(), Some(MatrixError::RaggedRow { row: 1, expected: 2, found: 1 }));
+		assert_eq!(Matrix::from_reader("1 x\n".as_bytes()).err(), Some(MatrixError::Parse { line: 1, token: String::from("x") }));
+	}
+
+	#[test]
+	fn verify_inverse_checks_the_round_trip()
+	{
+		assert_eq!(mat(&[&[4f64, 7f64], &[2f64, 6f64]]).verify_inverse(1e-12), Ok(true));
+		assert_eq!(mat(&[&[1f64, 2f64], &[2f64, 4f64]]).verify_inverse(1e-12), Err(MatrixError::Singular));
+		assert_eq!(Matrix::new(2, 3).verify_inverse(1e-12), Err(MatrixError::NotSquare { m: 2, n: 3 }));
+	}
+
+	#[test]
+	fn replaying_rref_steps_reproduces_the_rref()
+	{
+		let a              = mat(&[&[0f64, 2f64, 4f64], &[1f64, 1f64, 1f64], &[3f64, 5f64, 7f64]]);
+		let (reduced, ops) = a.rref_steps();
+
+		let mut replay = a.clone();
+
+		for op in &ops
+		{
+			replay.apply_row_op(op);
+		}
+
+		assert!(ops.iter().any(|op| matches!(op, RowOp::Swap(..))));
+		assert_close(&replay, &reduced, 0f64);
+		assert_close(&reduced, &a.rref(), 0f64);
+		assert_close(&reduced, &mat(&[&[1f64, 0f64, -1f64], &[0f64, 1f64, 2f64], &[0f64, 0f64, 0f64]]), 1e-12);
+	}
+
+	#[test]
+	fn apply_columns_matches_mul_vec_per_column()
+	{
+		let a   = mat(&[&[1f64, 2f64, 0f64], &[-1f64, 0f64, 3f64]]);
+		let x   = mat(&[&[1f64, 4f64], &[2f64, 5f64], &[3f64, 6f64]]);
+		let res = a.apply_columns(&x).unwrap();
+
+		for k in 0..2
+		{
+			assert_eq!(res.get_col_vec(k), a.mul_vec(&x.get_col_vec(k)).unwrap());
+		}
+
+		assert_eq!(a.apply_columns(&a).err(), Some(MatrixError::DimensionMismatch { expected: (3, 3), found: (2, 3) }));
+	}
+
+	#[test]
+	fn symmetric_eig_returns_orthogonal_eigenpairs()
+	{
+		let a           = mat(&[&[4f64, 1f64, 2f64], &[1f64, 3f64, 0f64], &[2f64, 0f64, 5f64]]);
+		let (values, v) = a.symmetric_eig().unwrap();
+
+		assert!(values.windows(2).all(|pair| pair[0] >= pair[1]));
+		assert_close(&v.transpose().mul(&v), &Matrix::identity(3), 1e-10);
+
+		for (k, lambda) in values.iter().enumerate()
+		{
+			let vk = v.get_col_vec(k);
+			let av = a.mul_vec(&vk).unwrap();
+			assert!(av.iter().zip(vk.iter()).all(|(l, r)| (l - lambda * r).abs() < 1e-10));
+		}
+
+		assert_eq!(mat(&[&[1f64, 2f64], &[3f64, 4f64]]).symmetric_eig().err(), Some(MatrixError::NotSymmetric));
+		assert_eq!(mat(&[&[1e-20, 1e-15], &[0f64, 1e-20]]).symmetric_eig().err(), Some(MatrixError::NotSymmetric));
+		assert!(mat(&[&[1e-20, 1e-15], &[1e-15, 1e-20]]).symmetric_eig().is_ok());
+	}
+
+	#[test]
+	fn det_with_tol_flushes_numerical_singularity()
+	{
+		let a = mat(&[&[0.1, 0.2, 0.3], &[0.4, 0.5, 0.6], &[0.7, 0.8, 0.9]]);
+
+		assert_eq!(a.det_with_tol(DEFAULT_TOL), Ok(0f64));
+		assert!((mat(&[&[2f64, 1f64], &[1f64, 3f64]]).det_with_tol(DEFAULT_TOL).unwrap() - 5f64).abs() < 1e-12);
+	}
+
+	#[test]
+	fn tile_repeats_the_pattern()
+	{
+		let a = mat(&[&[1f64, 2f64], &[3f64, 4f64]]);
+
+		let expected = vec![
+			vec![1f64, 2f64, 1f64, 2f64],
+			vec![3f64, 4f64, 3f64, 4f64],
+			vec![1f64, 2f64, 1f64, 2f64],
+			vec![3f64, 4f64, 3f64, 4f64],
+		];
+
+		let wide = a.tile(1, 3);
+
+		assert_eq!(a.tile(2, 2).to_nested(), expected);
+		assert_eq!((wide.m, wide.n), (2, 6));
+	}
+
+	#[test]
+	#[should_panic(expected = "cannot tile a matrix beyond u32 dimensions")]
+	fn tile_rejects_overflowing_dimensions()
+	{
+		Matrix::new(70000, 1).tile(70000, 1);
+	}
+
+	#[test]
+	fn schur_complement_matches_hand_computation()
+	{
+		let a = mat(&[
+			&[2f64, 0f64, 1f64, 2f64],
+			&[0f64, 4f64, 0f64, 4f64],
+			&[2f64, 0f64, 5f64, 1f64],
+			&[1f64, 1f64, 2f64, 6f64],
+		]);
+
+		assert_close(&a.schur_complement(2).unwrap(), &mat(&[&[4f64, -1f64], &[1.5, 4f64]]), 1e-12);
+		assert_eq!(a.schur_complement(4).err(), Some(MatrixError::OutOfBounds { index: 4, bound: 4 }));
+		assert_eq!(Matrix::new(3, 3).schur_complement(1).err(), Some(MatrixError::Singular));
+	}
+
+	#[test]
+	fn fold_computes_custom_aggregates()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 0.5, -1f64]]);
+
+		assert_eq!(a.fold(1f64, |acc, entry| acc * entry), -12f64);
+		assert_eq!(a.fold(0usize, |count, entry| if entry > 1f64 { count + 1 } else { count }), 3);
+	}
+
+	#[test]
+	fn sqrt_spd_squares_back()
+	{
+		let a    = mat(&[&[4f64, 1f64, 0f64], &[1f64, 3f64, 1f64], &[0f64, 1f64, 2f64]]);
+		let root = a.sqrt_spd().unwrap();
+
+		assert_close(&root.mul(&root), &a, 1e-10);
+		assert_close(&root, &root.transpose(), 1e-10);
+		assert_eq!(mat(&[&[1f64, 2f64], &[2f64, 1f64]]).sqrt_spd().err(), Some(MatrixError::NotPositiveDefinite));
+	}
+
+	#[test]
+	fn eigen_invariants_hold_for_symmetric_input()
+	{
+		let a = mat(&[&[4f64, 1f64, 2f64], &[1f64, 3f64, 0f64], &[2f64, 0f64, 5f64]]);
+
+		assert_eq!(a.eigen_invariants_ok(1e-9), Ok(true));
+
+		/*
+			Shifting an amount from one eigenvalue to another keeps the trace
+			but not the determinant, and rescaling two of them inversely keeps
+			the determinant but not the trace.
 		*/
-		for i in 1..=(self.m * self.n)
+		let (values, _)  = a.symmetric_eig().unwrap();
+		let (trace, det) = (a.trace().unwrap(), a.det());
+		let mut balanced = values.clone();
+		let mut rescaled = values.clone();
+
+		balanced[0] += 1e-3;
+		balanced[1] -= 1e-3;
+		rescaled[0] *= 1f64 + 1e-3;
+		rescaled[1] /= 1f64 + 1e-3;
+
+		assert!(Matrix::invariants_match(&values, trace, det, 1e-9));
+		assert!(!Matrix::invariants_match(&balanced, trace, det, 1e-9));
+		assert!(!Matrix::invariants_match(&rescaled, trace, det, 1e-9));
+		assert_eq!(mat(&[&[1f64, 2f64], &[0f64, 1f64]]).eigen_invariants_ok(1e-9), Err(MatrixError::NotSymmetric));
+	}
+
+	#[test]
+	fn pad_to_pow2_and_trim_round_trip()
+	{
+		let a      = Matrix::new(3, 5).map_indexed(|i, j, _| (i * 5 + j + 1) as f64);
+		let padded = a.pad_to_pow2();
+
+		assert_eq!((padded.m, padded.n), (4, 8));
+		assert_eq!(padded.fold(0f64, |acc, entry| acc + entry), a.fold(0f64, |acc, entry| acc + entry));
+		assert_eq!(padded.trim(3, 5).to_nested(), a.to_nested());
+	}
+
+	#[test]
+	fn pivot_columns_identify_basic_variables()
+	{
+		let a = mat(&[&[1f64, 2f64, 0f64, 3f64], &[0f64, 0f64, 1f64, 4f64], &[1f64, 2f64, 1f64, 7f64]]);
+
+		assert_eq!(a.pivot_columns(DEFAULT_TOL), vec![0, 2]);
+		assert_eq!(Matrix::identity(3).pivot_columns(DEFAULT_TOL), vec![0, 1, 2]);
+	}
+
+	#[test]
+	fn from_fn_builds_an_identity()
+	{
+		let a = Matrix::from_fn(3, 3, |i, j| if i == j { 1f64 } else { 0f64 });
+
+		assert!(a.is_identity(0f64));
+		assert_eq!(Matrix::from_fn(2, 3, |i, j| (i + j) as f64).to_nested(), vec![vec![0f64, 1f64, 2f64], vec![1f64, 2f64, 3f64]]);
+	}
+
+	#[test]
+	fn filled_sets_every_entry()
+	{
+		assert_eq!(Matrix::filled(2, 2, 7f64).to_nested(), vec![vec![7f64; 2]; 2]);
+		assert!(Matrix::new(2, 3).is_zero(0f64));
+	}
+
+	#[test]
+	fn det_cache_is_invalidated_by_mutation()
+	{
+		let mut a = mat(&[&[2f64, 1f64], &[1f64, 3f64]]);
+
+		assert_eq!(a.det_cache.get(), None);
+		assert_eq!(a.det(), 5f64);
+		assert_eq!(a.det_cache.get(), Some(&5f64));
+		assert_eq!(a.det(), 5f64);
+
+		a.set(0, 0, 4f64);
+		assert_eq!(a.det_cache.get(), None);
+		assert_eq!(a.det(), 11f64);
+
+		a.scale_assign(2f64);
+		assert_eq!(a.det(), 44f64);
+		assert_eq!(a.clone().det_cache.get(), None);
+	}
+
+	#[test]
+	fn max_abs_diff_reports_the_worst_entry()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+		let b = a.map_indexed(|i, j, entry| if (i, j) == (1, 2) { entry - 0.5 } else { entry });
+
+		assert_eq!(a.max_abs_diff(&b), Ok((0.5, 1, 2)));
+		assert!(matches!(a.max_abs_diff(&Matrix::new(3, 2)), Err(MatrixError::DimensionMismatch { .. })));
+		assert_eq!(Matrix::new(0, 0).max_abs_diff(&Matrix::new(0, 0)), Err(MatrixError::EmptyInput));
+	}
+
+	#[test]
+	fn set_diagonal_only_touches_the_diagonal()
+	{
+		let mut a = Matrix::filled(2, 3, 9f64);
+
+		a.set_diagonal(&[1f64, 2f64]).unwrap();
+
+		assert_eq!(a.to_nested(), vec![vec![1f64, 9f64, 9f64], vec![9f64, 2f64, 9f64]]);
+		assert_eq!(a.set_diagonal(&[1f64, 2f64, 3f64]), Err(MatrixError::InvalidLength { expected: 2, found: 3 }));
+	}
+
+	fn npy_with_header(header: &str, data: &[u8]) -> Vec<u8>
+	{
+		let mut bytes = NPY_MAGIC.to_vec();
+
+		bytes.extend_from_slice(&[1, 0]);
+		bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+		bytes.extend_from_slice(header.as_bytes());
+		bytes.extend_from_slice(data);
+
+		bytes
+	}
+
+	#[test]
+	fn npy_round_trip()
+	{
+		let a     = mat(&[&[1f64, -2.5, 3f64], &[4f64, 5f64, 1e-300]]);
+		let bytes = a.to_npy();
+
+		assert_eq!(bytes[..NPY_MAGIC.len()], *NPY_MAGIC);
+		assert!((NPY_MAGIC.len() + 4 + u16::from_le_bytes([bytes[8], bytes[9]]) as usize).is_multiple_of(64));
+		assert_eq!(Matrix::from_npy(&bytes).unwrap().to_nested(), a.to_nested());
+	}
+
+	#[test]
+	fn npy_rejects_unsupported_headers()
+	{
+		let data    = [0u8; 16];
+		let invalid = |reason: &str| Some(MatrixError::InvalidNpy(String::from(reason)));
+
+		let fortran = npy_with_header("{'descr': '<f8', 'fortran_order': True, 'shape': (2, 1), }\n", &data);
+		let dtype   = npy_with_header("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 2), }\n", &data);
+		let huge    = npy_with_header("{'descr': '<f8', 'fortran_order': False, 'shape': (2147483648, 1073741824), }\n", &[]);
+		let short   = npy_with_header("{'descr': '<f8', 'fortran_order': False, 'shape': (3, 1), }\n", &data);
+
+		assert_eq!(Matrix::from_npy(&fortran).err(), invalid("array must be in C order"));
+		assert_eq!(Matrix::from_npy(&dtype).err(), invalid("dtype must be '<f8'"));
+		assert_eq!(Matrix::from_npy(&huge).err(), invalid("shape too large"));
+		assert_eq!(Matrix::from_npy(&short).err(), invalid("data length does not match shape"));
+		assert_eq!(Matrix::from_npy(b"not numpy").err(), invalid("missing magic string"));
+	}
+
+	#[test]
+	fn powf_is_element_wise()
+	{
+		let a = mat(&[&[1f64, -2f64], &[3f64, 0.5]]);
+
+		assert_close(&a.powf(2f64), &a.map(|entry| entry * entry), 1e-12);
+		assert!(a.powf(0.5).entries[1].is_nan());
+	}
+
+	#[test]
+	fn orient2d_classifies_turns()
+	{
+		assert_eq!(orient2d((0f64, 0f64), (1f64, 0f64), (0f64, 1f64)), 1);
+		assert_eq!(orient2d((0f64, 0f64), (0f64, 1f64), (1f64, 0f64)), -1);
+		assert_eq!(orient2d((0f64, 0f64), (1f64, 1f64), (2f64, 2f64)), 0);
+		assert_eq!(orient2d((0.1, 0.1), (0.2, 0.2), (0.3, 0.3)), 0);
+		assert_eq!(orient2d((0f64, 0f64), (1e-7, 0f64), (0f64, 1e-7)), 1);
+	}
+
+	#[test]
+	fn orient3d_classifies_sides()
+	{
+		let (a, b, c) = ((0f64, 0f64, 0f64), (1f64, 0f64, 0f64), (0f64, 1f64, 0f64));
+
+		assert_eq!(orient3d(a, b, c, (0f64, 0f64, 1f64)), 1);
+		assert_eq!(orient3d(a, b, c, (0f64, 0f64, -1f64)), -1);
+		assert_eq!(orient3d(a, b, c, (0.3, 0.7, 0f64)), 0);
+		assert_eq!(orient3d(a, (1e-7, 0f64, 0f64), (0f64, 1e-7, 0f64), (0f64, 0f64, 1e-7)), 1);
+	}
+
+	#[test]
+	fn split_ldu_sums_back()
+	{
+		let a         = Matrix::from_fn(3, 3, |i, j| (i * 3 + j + 1) as f64);
+		let (l, d, u) = a.split_ldu().unwrap();
+
+		assert_eq!(l.add(&d).add(&u).to_nested(), a.to_nested());
+		assert!(l.iter_nonzero(0f64).all(|(i, j, _)| i > j));
+		assert!(d.is_diagonal(0f64));
+		assert!(u.iter_nonzero(0f64).all(|(i, j, _)| i < j));
+		assert_eq!(Matrix::new(2, 3).split_ldu().err(), Some(MatrixError::NotSquare { m: 2, n: 3 }));
+	}
+
+	#[test]
+	fn iterative_callbacks_see_decreasing_residuals()
+	{
+		let a = mat(&[&[4f64, 1f64, 0f64], &[1f64, 5f64, 2f64], &[0f64, 2f64, 6f64]]);
+		let b = [1f64, 2f64, 3f64];
+
+		for gauss_seidel in [false, true]
 		{
-			let entry = self.entries[(i-1) as usize];
+			let mut residuals = Vec::new();
+			let callback      = |iter: usize, res: f64| residuals.push((iter, res));
 
-			/* 
-				We will produce a newline if we are on the n-th (n = width) 
-				element within a row. We use a simple modulus check to determine this.
-			*/
-			if i % self.n == 0
+			let x = if gauss_seidel
 			{
-				write!(f, "{}\n", entry)?;
+				a.solve_gauss_seidel_with_callback(&b, 200, 1e-12, callback).unwrap()
 			}
 			else
 			{
-				write!(f, "{} ", entry)?;
+				a.solve_jacobi_with_callback(&b, 200, 1e-12, callback).unwrap()
+			};
+
+			assert!(a.residual(&x, &b).unwrap() < 1e-12);
+			assert!(residuals.iter().enumerate().all(|(k, &(iter, _))| iter == k + 1));
+			assert!(residuals.windows(2).all(|pair| pair[1].1 < pair[0].1));
+		}
+
+		assert_eq!(a.solve_jacobi(&b, 2, 1e-12), Err(MatrixError::DidNotConverge { iterations: 2 }));
+		assert_eq!(mat(&[&[0f64, 1f64], &[1f64, 0f64]]).solve_gauss_seidel(&[1f64, 1f64], 10, 1e-12), Err(MatrixError::ZeroDiagonal { index: 0 }));
+	}
+
+	#[test]
+	fn qr_pivot_reveals_rank()
+	{
+		let a            = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64], &[7f64, 8f64, 9f64], &[1f64, 0f64, 1f64]]);
+		let (q, r, perm) = a.qr_pivot().unwrap();
+		let permuted     = Matrix::from_fn(a.m, a.n, |i, j| a.entries[a.get_index(i, perm[j as usize] as u32)]);
+
+		assert_close(&q.mul(&r), &permuted, 1e-10);
+		assert_close(&q.transpose().mul(&q), &Matrix::identity(q.m), 1e-10);
+
+		let diag = (0..r.n).map(|k| r.entries[r.get_index(k, k)].abs()).collect::<Vec<_>>();
+		assert!(diag.windows(2).all(|pair| pair[1] <= pair[0]));
+		assert_eq!(Matrix::new(0, 2).qr_pivot().err(), Some(MatrixError::EmptyInput));
+	}
+
+	#[test]
+	fn like_constructors_copy_dimensions()
+	{
+		let a = Matrix::filled(2, 3, 5f64);
+
+		assert_eq!(a.zeros_like().to_nested(), vec![vec![0f64; 3]; 2]);
+		assert_eq!(a.ones_like().to_nested(), vec![vec![1f64; 3]; 2]);
+		assert_eq!(a.identity_like().err(), Some(MatrixError::NotSquare { m: 2, n: 3 }));
+		assert!(Matrix::filled(3, 3, 5f64).identity_like().unwrap().is_identity(0f64));
+	}
+
+	#[test]
+	fn dimension_accessors()
+	{
+		let a = Matrix::new(2, 3);
+
+		assert_eq!((a.rows(), a.cols()), (2, 3));
+		assert_eq!(a.shape(), (2, 3));
+		assert_eq!(a.transpose().shape(), (3, 2));
+	}
+
+	#[test]
+	fn frobenius_inner_matches_trace()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+		let b = mat(&[&[-1f64, 0.5, 2f64], &[3f64, 1f64, -2f64]]);
+
+		assert_eq!(a.frobenius_inner(&b), Ok(11f64));
+		assert_eq!(a.frobenius_inner(&b), a.transpose().mul(&b).trace());
+		assert_eq!(a.frobenius_inner(&a.transpose()), Err(MatrixError::DimensionMismatch { expected: (2, 3), found: (3, 2) }));
+	}
+
+	#[test]
+	fn interleave_rows_alternates()
+	{
+		let a = mat(&[&[1f64, 2f64], &[3f64, 4f64]]);
+		let b = mat(&[&[5f64, 6f64], &[7f64, 8f64]]);
+
+		assert_eq!(a.interleave_rows(&b).unwrap().to_nested(), vec![vec![1f64, 2f64], vec![5f64, 6f64], vec![3f64, 4f64], vec![7f64, 8f64]]);
+		assert_eq!(a.interleave_rows(&Matrix::new(1, 2)).err(), Some(MatrixError::DimensionMismatch { expected: (2, 2), found: (1, 2) }));
+		assert_eq!(a.interleave_rows(&Matrix::new(2, 3)).err(), Some(MatrixError::DimensionMismatch { expected: (2, 2), found: (2, 3) }));
+
+		let tall = Matrix::new(u32::MAX / 2 + 1, 0);
+		assert_eq!(tall.interleave_rows(&tall).err(), Some(MatrixError::DimensionOverflow));
+	}
+
+	#[test]
+	fn det_and_lu_reuses_the_factorization()
+	{
+		let a                 = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64], &[7f64, 8f64, 10f64]]);
+		let (det, l, u, perm) = a.det_and_lu().unwrap();
+		let permuted          = Matrix::from_fn(a.m, a.n, |i, j| a.entries[a.get_index(perm[i as usize] as u32, j)]);
+
+		assert!((det - a.det()).abs() < 1e-10);
+		assert_close(&l.mul(&u), &permuted, 1e-12);
+	}
+
+	#[test]
+	fn spectral_radius_of_known_spectra()
+	{
+		let negative  = mat(&[&[2f64, 0f64], &[0f64, -5f64]]);
+		let laplacian = mat(&[&[1f64, -1f64, 0f64], &[-1f64, 2f64, -1f64], &[0f64, -1f64, 1f64]]);
+
+		assert!((negative.spectral_radius(1000, 1e-12).unwrap() - 5f64).abs() < 1e-8);
+		assert!((laplacian.spectral_radius(1000, 1e-12).unwrap() - 3f64).abs() < 1e-8);
+	}
+
+	#[test]
+	fn min_max_normalize_maps_onto_unit_interval()
+	{
+		let a = mat(&[&[2f64, 4f64], &[6f64, 10f64]]);
+
+		assert_eq!(a.min_max_normalize().to_nested(), vec![vec![0f64, 0.25], vec![0.5, 1f64]]);
+		assert!(Matrix::filled(2, 3, 4f64).min_max_normalize().is_zero(0f64));
+	}
+
+	#[test]
+	fn inverse_adjugate_agrees_with_inverse()
+	{
+		let a = mat(&[&[4f64, 7f64, 2f64], &[3f64, 6f64, 1f64], &[2f64, 5f64, 3f64]]);
+		assert_close(&a.inverse_adjugate().unwrap(), &a.inverse().unwrap(), 1e-12);
+
+		let unimodular = mat(&[&[2f64, 3f64, 1f64], &[1f64, 2f64, 1f64], &[1f64, 1f64, 1f64]]);
+		let inv        = unimodular.inverse_adjugate().unwrap();
+
+		assert_eq!(inv.to_nested(), vec![vec![1f64, -2f64, 1f64], vec![0f64, 1f64, -1f64], vec![-1f64, 1f64, 1f64]]);
+		assert!(unimodular.mul(&inv).is_identity(0f64));
+
+		let singular = Matrix::from_fn(3, 3, |i, j| (i * 3 + j + 1) as f64 / 10f64);
+		assert_eq!(singular.inverse_adjugate().err(), Some(MatrixError::Singular));
+		assert_eq!(singular.inverse().err(), Some(MatrixError::Singular));
+
+		let tiny = Matrix::identity(2).map(|entry| entry * 1e-13);
+		assert_eq!(tiny.inverse_adjugate().unwrap().to_nested(), vec![vec![1e13, 0f64], vec![0f64, 1e13]]);
+		assert_eq!(singular.map(|entry| entry * 1e8).inverse_adjugate().err(), Some(MatrixError::Singular));
+		assert_eq!(Matrix::identity(11).inverse_adjugate().err(), Some(MatrixError::TooLargeForCofactor { m: 11 }));
+	}
+
+	#[test]
+	fn from_columns_lays_out_columns()
+	{
+		let a = Matrix::from_columns(vec![vec![1f64, 2f64, 3f64], vec![4f64, 5f64, 6f64]]).unwrap();
+
+		assert_eq!(a.to_nested(), vec![vec![1f64, 4f64], vec![2f64, 5f64], vec![3f64, 6f64]]);
+		assert_eq!(Matrix::from_columns(vec![vec![1f64, 2f64], vec![3f64]]).err(), Some(MatrixError::InvalidLength { expected: 2, found: 1 }));
+	}
+
+	#[test]
+	fn lu_swap_count_counts_interchanges()
+	{
+		let cycle = mat(&[&[0f64, 0f64, 1f64], &[1f64, 0f64, 0f64], &[0f64, 1f64, 0f64]]);
+
+		assert_eq!(Matrix::identity(3).lu_swap_count(), Ok(0));
+		assert_eq!(mat(&[&[0f64, 1f64], &[1f64, 0f64]]).lu_swap_count(), Ok(1));
+		assert_eq!(cycle.lu_swap_count(), Ok(2));
+		assert_eq!(mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64], &[7f64, 8f64, 10f64]]).lu_swap_count(), Ok(2));
+	}
+
+	#[test]
+	fn elementwise_max_min_select_per_entry()
+	{
+		let a = mat(&[&[1f64, 5f64], &[3f64, -2f64]]);
+		let b = mat(&[&[2f64, 4f64], &[3f64, -7f64]]);
+
+		assert_eq!(a.elementwise_max(&b).unwrap().to_nested(), vec![vec![2f64, 5f64], vec![3f64, -2f64]]);
+		assert_eq!(a.elementwise_min(&b).unwrap().to_nested(), vec![vec![1f64, 4f64], vec![3f64, -7f64]]);
+		assert_eq!(a.elementwise_max(&Matrix::new(2, 3)).err(), Some(MatrixError::DimensionMismatch { expected: (2, 2), found: (2, 3) }));
+		assert_eq!(a.elementwise_min(&Matrix::new(3, 2)).err(), Some(MatrixError::DimensionMismatch { expected: (2, 2), found: (3, 2) }));
+	}
+
+	#[test]
+	fn integral_image_matches_brute_force()
+	{
+		let a     = Matrix::from_fn(3, 4, |i, j| ((i * 7 + j * 3) % 5) as f64 - 2f64);
+		let table = a.integral_image();
+		let sum   = |r0: u32, c0: u32, r1: u32, c1: u32| {
+			(r0..=r1).flat_map(|i| (c0..=c1).map(move |j| (i, j))).map(|(i, j)| a.entries[a.get_index(i, j)]).sum::<f64>()
+		};
+		let at    = |i: u32, j: u32| table.entries[table.get_index(i, j)];
+
+		for i in 0..3
+		{
+			for j in 0..4
+			{
+				assert_eq!(at(i, j), sum(0, 0, i, j));
 			}
 		}
 
-		Ok(())
+		assert_eq!(at(2, 3) - at(0, 3) - at(2, 0) + at(0, 0), sum(1, 1, 2, 3));
 	}
-}
 
-fn main()
-{
-	let mut mat = Matrix::new(3, 3);
-	let mut rng = rand::thread_rng();
+	#[test]
+	fn duplicate_rows_reports_pairs()
+	{
+		let a = mat(&[&[1f64, 2f64], &[3f64, 4f64], &[1f64, 2f64 + 1e-13], &[5f64, 6f64]]);
 
-	for i in 0..(mat.m * mat.n)
+		assert_eq!(a.duplicate_rows(1e-9), vec![(0, 2)]);
+		assert!(a.duplicate_rows(0f64).is_empty());
+	}
+
+	#[test]
+	fn solvability_classifies_systems()
 	{
-		mat.entries[i as usize] = rng.gen_range(1.0f64..20.0f64);
+		let deficient = mat(&[&[1f64, 2f64], &[2f64, 4f64]]);
+
+		assert_eq!(mat(&[&[2f64, 1f64], &[1f64, 3f64]]).solvability(&[3f64, 5f64]), Ok(Solvability::UniqueSolution));
+		assert_eq!(deficient.solvability(&[1f64, 3f64]), Ok(Solvability::NoSolution));
+		assert_eq!(deficient.solvability(&[1f64, 2f64]), Ok(Solvability::InfiniteSolutions));
+		assert_eq!(deficient.solvability(&[1f64]), Err(MatrixError::InvalidLength { expected: 2, found: 1 }));
+
+		let large = Matrix::from_fn(3, 3, |i, j| (3 * i + j + 1) as f64 * 1e5);
+		let small = Matrix::identity(2).map(|entry| entry * 1e-13);
+
+		assert_eq!(large.solvability(&[6e5, 15e5, 24e5]), Ok(Solvability::InfiniteSolutions));
+		assert_eq!(large.solvability(&[6e5, 15e5, 25e5]), Ok(Solvability::NoSolution));
+		assert_eq!(small.solvability(&[1f64, 1f64]), Ok(Solvability::UniqueSolution));
+		assert_eq!(small.solvability(&[1e-13, 0f64]), Ok(Solvability::UniqueSolution));
 	}
 
-	let mut mat2 = Matrix::new(3, 3);
+	#[test]
+	fn trace_of_power_matches_eigenvalue_sums()
+	{
+		let a         = mat(&[&[2f64, 1f64, 0f64], &[1f64, 3f64, 1f64], &[0f64, 1f64, 4f64]]);
+		let (vals, _) = a.symmetric_eig().unwrap();
 
-	for i in 0..(mat2.m * mat2.n)
+		for k in 0..5
+		{
+			let expected = vals.iter().map(|lambda| lambda.powi(k as i32)).sum::<f64>();
+			assert!((a.trace_of_power(k).unwrap() - expected).abs() < 1e-9 * expected.abs().max(1f64));
+		}
+
+		assert_eq!(a.trace_of_power(0), Ok(3f64));
+		assert_eq!(Matrix::new(2, 3).trace_of_power(2).err(), Some(MatrixError::NotSquare { m: 2, n: 3 }));
+	}
+
+	#[test]
+	fn flips_reverse_and_undo()
 	{
-		mat2.entries[i as usize] = rng.gen_range(1.0f64..20.0f64);
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+
+		assert_eq!(a.flip_horizontal().to_nested(), vec![vec![3f64, 2f64, 1f64], vec![6f64, 5f64, 4f64]]);
+		assert_eq!(a.flip_vertical().to_nested(), vec![vec![4f64, 5f64, 6f64], vec![1f64, 2f64, 3f64]]);
+		assert_eq!(a.flip_horizontal().flip_horizontal().to_nested(), a.to_nested());
+		assert_eq!(a.flip_vertical().flip_vertical().to_nested(), a.to_nested());
 	}
 
-	println!("{}", mat);
-	println!("{}", mat2);
-	println!("det(mat) = {}", mat.det());
-	println!("sum: {}", mat.add(&mat2));
-	println!("mul: {}", mat.mul(&mat2));
+	#[test]
+	fn rot90_turns_clockwise()
+	{
+		let a = mat(&[&[1f64, 2f64, 3f64], &[4f64, 5f64, 6f64]]);
+
+		assert_eq!(a.rot90(0).to_nested(), a.to_nested());
+		assert_eq!(a.rot90(1).to_nested(), vec![vec![4f64, 1f64], vec![5f64, 2f64], vec![6f64, 3f64]]);
+		assert_eq!(a.rot90(2).to_nested(), vec![vec![6f64, 5f64, 4f64], vec![3f64, 2f64, 1f64]]);
+		assert_eq!(a.rot90(3).to_nested(), vec![vec![3f64, 6f64], vec![2f64, 5f64], vec![1f64, 4f64]]);
+		assert_eq!(a.rot90(4).to_nested(), a.to_nested());
+	}
+
+	#[test]
+	fn gemm_matches_composed_operations()
+	{
+		let (a, b, c)     = (random(3, 4), random(4, 2), random(3, 2));
+		let (alpha, beta) = (1.5, -0.5);
+		let expected      = a.mul(&b).map(|entry| alpha * entry).add(&c.map(|entry| beta * entry));
+
+		assert_close(&a.gemm(&b, &c, alpha, beta).unwrap(), &expected, 1e-12);
+		assert!(matches!(a.gemm(&c, &c, alpha, beta), Err(MatrixError::DimensionMismatch { .. })));
+		assert_eq!(a.gemm(&b, &Matrix::new(2, 2), alpha, beta).err(), Some(MatrixError::DimensionMismatch { expected: (3, 2), found: (2, 2) }));
+	}
 }